@@ -1,11 +1,13 @@
+use crate::auth::TenantCredential;
+use crate::config::CorsConfig;
 use crate::engine::{PlanOutcome, RouterEngine};
-use crate::errors::{with_context, ApiError, RouterError};
+use crate::errors::{with_context, ApiError, ErrorBody, RouterError};
 use crate::types::{
-    CacheStatus, CatalogDocument, ContentLevel, PolicyDocument, RouteFeedback, RouteRequest,
-    TraceCtx,
+    CacheStatus, CatalogDocument, ContentLevel, OrgCtx, PolicyDocument, RouteFeedback,
+    RouteRequest, RoutePlan, TraceCtx,
 };
 use actix_web::http::header;
-use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{get, middleware, post, web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
 use serde::Serialize;
 use std::time::Instant;
@@ -20,15 +22,21 @@ struct TraceHeaderCtx {
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(post_route_plan)
+        .service(post_route_plan_batch)
         .service(post_route_feedback)
         .service(get_capabilities)
         .service(get_catalog)
         .service(get_policy)
         .service(get_stats)
+        .service(get_metrics)
         .service(get_health)
-        .service(reload_policy)
-        .service(reload_catalog)
-        .service(reload_overlays);
+        .service(
+            web::scope("/admin")
+                .wrap(middleware::from_fn(crate::auth::require_admin_scope))
+                .service(reload_policy)
+                .service(reload_catalog)
+                .service(reload_overlays),
+        );
 }
 
 #[post("/route/plan")]
@@ -64,6 +72,24 @@ async fn post_route_plan(
         ));
     }
     let request_id = request.request_id.clone();
+    if let Some(credential) = http_req.extensions().get::<TenantCredential>() {
+        if !credential.permits_alias(&request.alias) {
+            return Err(with_context(
+                RouterError::Forbidden(format!(
+                    "tenant '{}' may not request alias '{}'",
+                    credential.tenant, request.alias
+                )),
+                Some(request_id.clone()),
+                Some(policy_rev_hint.clone()),
+            ));
+        }
+        let role = request.org.as_ref().and_then(|org| org.role.clone());
+        request.org = Some(OrgCtx {
+            tenant: Some(credential.tenant.clone()),
+            project: credential.project.clone(),
+            role,
+        });
+    }
     if let Some(ip) = client_ip(&http_req) {
         if let Err(err) = engine.check_rate_limit(&ip) {
             return Err(with_context(
@@ -87,9 +113,20 @@ async fn post_route_plan(
         }
     }
     let trace_snapshot = request.trace.clone();
+    let stream_requested = request.stream;
+    let request_params = request.params.clone();
     let outcome = engine.plan(request).await.map_err(|err| {
         with_context(err, Some(request_id.clone()), Some(policy_rev_hint.clone()))
     })?;
+    if stream_requested {
+        return Ok(crate::stream::proxy_stream(
+            engine.clone(),
+            engine.http_client(),
+            request_params,
+            outcome.plan,
+        )
+        .await);
+    }
     let elapsed = started.elapsed();
     let trace_headers = TraceHeaderCtx {
         traceparent: trace_snapshot
@@ -147,6 +184,154 @@ fn respond_with_plan(
     response.json(outcome.plan)
 }
 
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum BatchPayload {
+    Wrapped { items: Vec<RouteRequest> },
+    Bare(Vec<RouteRequest>),
+}
+
+impl BatchPayload {
+    fn into_items(self) -> Vec<RouteRequest> {
+        match self {
+            BatchPayload::Wrapped { items } => items,
+            BatchPayload::Bare(items) => items,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BatchItemResult {
+    request_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ok: Option<RoutePlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorBody>,
+}
+
+#[derive(Serialize)]
+struct BatchResponse {
+    schema_version: &'static str,
+    results: Vec<BatchItemResult>,
+}
+
+#[post("/route/plan:batch")]
+async fn post_route_plan_batch(
+    http_req: HttpRequest,
+    engine: web::Data<RouterEngine>,
+    payload: web::Json<BatchPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let started = Instant::now();
+    let policy_rev_hint = engine.policy_revision();
+
+    if let Some(ip) = client_ip(&http_req) {
+        if let Err(err) = engine.check_rate_limit(&ip) {
+            return Err(with_context(err, None, Some(policy_rev_hint)));
+        }
+    }
+
+    let credential = http_req.extensions().get::<TenantCredential>().cloned();
+    let mut items = payload.into_inner().into_items();
+    let item_count = items.len();
+
+    let mut request_ids = Vec::with_capacity(item_count);
+    let mut precheck_errors: Vec<Option<RouterError>> = Vec::with_capacity(item_count);
+    for (idx, request) in items.iter_mut().enumerate() {
+        if request.schema_version.is_empty() {
+            request.schema_version = "1.1".into();
+        }
+        if request.request_id.is_empty() {
+            request.request_id = format!("batch-{idx}");
+        }
+        request_ids.push(request.request_id.clone());
+
+        let error = if !SUPPORTED_SCHEMAS
+            .iter()
+            .any(|schema| schema == &request.schema_version.as_str())
+        {
+            Some(RouterError::UnsupportedSchema {
+                provided: request.schema_version.clone(),
+                supported: SUPPORTED_SCHEMAS.iter().map(|s| s.to_string()).collect(),
+            })
+        } else if let Some(credential) = credential.as_ref() {
+            if !credential.permits_alias(&request.alias) {
+                Some(RouterError::Forbidden(format!(
+                    "tenant '{}' may not request alias '{}'",
+                    credential.tenant, request.alias
+                )))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        precheck_errors.push(error);
+
+        if let Some(credential) = credential.as_ref() {
+            let role = request.org.as_ref().and_then(|org| org.role.clone());
+            request.org = Some(OrgCtx {
+                tenant: Some(credential.tenant.clone()),
+                project: credential.project.clone(),
+                role,
+            });
+        }
+    }
+
+    let mut plannable_indices = Vec::with_capacity(item_count);
+    let mut plannable_requests = Vec::with_capacity(item_count);
+    for (idx, request) in items.into_iter().enumerate() {
+        if precheck_errors[idx].is_none() {
+            plannable_indices.push(idx);
+            plannable_requests.push(request);
+        }
+    }
+
+    let outcomes = engine.plan_batch(plannable_requests).await;
+    let mut outcome_slots: Vec<Option<Result<PlanOutcome, RouterError>>> =
+        (0..item_count).map(|_| None).collect();
+    for (idx, outcome) in plannable_indices.into_iter().zip(outcomes) {
+        outcome_slots[idx] = Some(outcome);
+    }
+
+    let mut results = Vec::with_capacity(item_count);
+    for (idx, request_id) in request_ids.into_iter().enumerate() {
+        let outcome = match precheck_errors[idx].take() {
+            Some(err) => Err(err),
+            None => outcome_slots[idx]
+                .take()
+                .unwrap_or_else(|| Err(RouterError::Planning("missing batch result".into()))),
+        };
+        results.push(match outcome {
+            Ok(outcome) => BatchItemResult {
+                request_id,
+                ok: Some(outcome.plan),
+                error: None,
+            },
+            Err(err) => {
+                let api_err = with_context(
+                    err,
+                    Some(request_id.clone()),
+                    Some(policy_rev_hint.clone()),
+                );
+                BatchItemResult {
+                    request_id,
+                    ok: None,
+                    error: Some(api_err.body()),
+                }
+            }
+        });
+    }
+
+    let elapsed = started.elapsed();
+    Ok(HttpResponse::Ok()
+        .append_header(("Router-Latency", format!("{}ms", elapsed.as_millis())))
+        .append_header(("Config-Revision", policy_rev_hint))
+        .json(BatchResponse {
+            schema_version: "1.1",
+            results,
+        }))
+}
+
 #[post("/route/feedback")]
 async fn post_route_feedback(
     engine: web::Data<RouterEngine>,
@@ -158,7 +343,11 @@ async fn post_route_feedback(
 }
 
 #[get("/capabilities")]
-async fn get_capabilities(engine: web::Data<RouterEngine>) -> Result<impl Responder, ApiError> {
+async fn get_capabilities(
+    http_req: HttpRequest,
+    engine: web::Data<RouterEngine>,
+    cors: web::Data<CorsConfig>,
+) -> Result<HttpResponse, ApiError> {
     #[derive(Serialize)]
     struct StickinessCaps {
         supported: bool,
@@ -171,6 +360,15 @@ async fn get_capabilities(engine: web::Data<RouterEngine>) -> Result<impl Respon
         supported: bool,
     }
 
+    #[derive(Serialize)]
+    struct CorsCaps {
+        supported: bool,
+        allow_any_origin: bool,
+        allowed_origins: Vec<String>,
+        allow_credentials: bool,
+        exposed_headers: Vec<String>,
+    }
+
     #[derive(Serialize)]
     struct CapabilityResponse {
         schema_version: &'static str,
@@ -179,6 +377,7 @@ async fn get_capabilities(engine: web::Data<RouterEngine>) -> Result<impl Respon
         batch: FeatureToggle,
         prefetch: FeatureToggle,
         provider_headers: bool,
+        cors: CorsCaps,
     }
 
     let policy = engine.policy_document();
@@ -188,15 +387,25 @@ async fn get_capabilities(engine: web::Data<RouterEngine>) -> Result<impl Respon
         max_turns: stickiness.max_turns,
         window_ms: stickiness.window_ms,
     };
+    let cors_caps = CorsCaps {
+        supported: true,
+        allow_any_origin: cors.allowed_origins.is_empty(),
+        allowed_origins: cors.allowed_origins.clone(),
+        allow_credentials: cors.allow_credentials && !cors.allowed_origins.is_empty(),
+        exposed_headers: cors.exposed_headers.clone(),
+    };
     let body = CapabilityResponse {
         schema_version: "1.1",
         privacy_modes: vec!["features_only", "summary", "full"],
         stickiness: stickiness_caps,
-        batch: FeatureToggle { supported: false },
+        batch: FeatureToggle { supported: true },
         prefetch: FeatureToggle { supported: false },
         provider_headers: true,
+        cors: cors_caps,
     };
-    Ok(HttpResponse::Ok().json(body))
+
+    let revision = engine.policy_revision();
+    Ok(revisioned_json_response(&http_req, &revision, body))
 }
 
 #[get("/catalog/models")]
@@ -204,32 +413,76 @@ async fn get_catalog(
     http_req: HttpRequest,
     engine: web::Data<RouterEngine>,
 ) -> Result<HttpResponse, ApiError> {
-    let doc = engine.catalog_document();
-    let revision = doc.revision.clone();
-    let strong = format!("\"{}\"", revision);
-    let weak = format!("W/\"{}\"", revision);
-    if let Some(if_none_match) = http_req.headers().get(header::IF_NONE_MATCH) {
-        if let Ok(value) = if_none_match.to_str() {
-            if value == strong || value == weak {
-                return Ok(HttpResponse::NotModified()
-                    .append_header(("ETag", strong))
-                    .append_header(("X-Catalog-Weak", weak.clone()))
-                    .append_header(("X-Catalog-Revision", revision))
-                    .finish());
-            }
-        }
+    let revision = engine.catalog_revision();
+    let etag = RevisionEtag::new(&revision);
+    if if_none_match(&http_req).is_some_and(|value| etag.matches(value)) {
+        return Ok(HttpResponse::NotModified()
+            .append_header(("ETag", etag.strong))
+            .append_header(("X-Catalog-Weak", etag.weak))
+            .append_header(("X-Catalog-Revision", revision))
+            .finish());
     }
 
     Ok(HttpResponse::Ok()
-        .append_header(("ETag", strong.clone()))
-        .append_header(("X-Catalog-Weak", weak))
+        .append_header(("ETag", etag.strong))
+        .append_header(("X-Catalog-Weak", etag.weak))
         .append_header(("X-Catalog-Revision", revision))
-        .json(doc))
+        .json(engine.catalog_snapshot_with_health()))
 }
 
 #[get("/policy")]
-async fn get_policy(engine: web::Data<RouterEngine>) -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(engine.policy_document()))
+async fn get_policy(
+    http_req: HttpRequest,
+    engine: web::Data<RouterEngine>,
+) -> Result<HttpResponse, ApiError> {
+    let revision = engine.policy_revision();
+    Ok(revisioned_json_response(
+        &http_req,
+        &revision,
+        engine.policy_document(),
+    ))
+}
+
+struct RevisionEtag {
+    strong: String,
+    weak: String,
+}
+
+impl RevisionEtag {
+    fn new(revision: &str) -> Self {
+        Self {
+            strong: format!("\"{}\"", revision),
+            weak: format!("W/\"{}\"", revision),
+        }
+    }
+
+    fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match == self.strong || if_none_match == self.weak
+    }
+}
+
+fn if_none_match(req: &HttpRequest) -> Option<&str> {
+    req.headers().get(header::IF_NONE_MATCH)?.to_str().ok()
+}
+
+fn revisioned_json_response<T: Serialize>(
+    req: &HttpRequest,
+    revision: &str,
+    body: T,
+) -> HttpResponse {
+    let etag = RevisionEtag::new(revision);
+    if if_none_match(req).is_some_and(|value| etag.matches(value)) {
+        return HttpResponse::NotModified()
+            .append_header(("ETag", etag.strong))
+            .append_header(("Config-Revision", revision.to_string()))
+            .append_header(("X-Policy-Rev", revision.to_string()))
+            .finish();
+    }
+    HttpResponse::Ok()
+        .append_header(("ETag", etag.strong))
+        .append_header(("Config-Revision", revision.to_string()))
+        .append_header(("X-Policy-Rev", revision.to_string()))
+        .json(body)
 }
 
 #[get("/stats")]
@@ -237,6 +490,13 @@ async fn get_stats(engine: web::Data<RouterEngine>) -> Result<impl Responder, Ap
     Ok(HttpResponse::Ok().json(engine.stats()))
 }
 
+#[get("/metrics")]
+async fn get_metrics(engine: web::Data<RouterEngine>) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(engine.render_metrics()))
+}
+
 #[get("/healthz")]
 async fn get_health(engine: web::Data<RouterEngine>) -> Result<impl Responder, ApiError> {
     #[derive(Serialize)]
@@ -257,7 +517,7 @@ async fn get_health(engine: web::Data<RouterEngine>) -> Result<impl Responder, A
     }))
 }
 
-#[post("/admin/policy")]
+#[post("/policy")]
 async fn reload_policy(
     engine: web::Data<RouterEngine>,
     payload: web::Json<PolicyDocument>,
@@ -269,7 +529,7 @@ async fn reload_policy(
     Ok(HttpResponse::NoContent())
 }
 
-#[post("/admin/catalog")]
+#[post("/catalog")]
 async fn reload_catalog(
     engine: web::Data<RouterEngine>,
     payload: web::Json<CatalogDocument>,
@@ -281,7 +541,7 @@ async fn reload_catalog(
     Ok(HttpResponse::NoContent())
 }
 
-#[post("/admin/overlays/reload")]
+#[post("/overlays/reload")]
 async fn reload_overlays(engine: web::Data<RouterEngine>) -> Result<impl Responder, ApiError> {
     engine.reload_overlays().await.map_err(ApiError::from)?;
     Ok(HttpResponse::NoContent())
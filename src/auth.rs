@@ -0,0 +1,183 @@
+use crate::engine::RouterEngine;
+use crate::errors::{with_context, RouterError};
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct TenantCredential {
+    pub tenant: String,
+    pub project: Option<String>,
+    pub allowed_aliases: Vec<String>,
+}
+
+impl TenantCredential {
+    pub fn permits_alias(&self, alias: &str) -> bool {
+        self.allowed_aliases.is_empty() || self.allowed_aliases.iter().any(|a| a == alias)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CredentialStore {
+    tokens: HashMap<String, TenantCredential>,
+}
+
+impl CredentialStore {
+    pub fn new(tokens: HashMap<String, TenantCredential>) -> Self {
+        Self { tokens }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn authenticate(&self, token: &str) -> Option<TenantCredential> {
+        self.tokens
+            .iter()
+            .find(|(candidate, _)| constant_time_eq(candidate.as_bytes(), token.as_bytes()))
+            .map(|(_, credential)| credential.clone())
+    }
+}
+
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if req.path().starts_with("/admin") {
+        return next.call(req).await;
+    }
+
+    let store = req.app_data::<web::Data<CredentialStore>>().cloned();
+    let Some(store) = store else {
+        return next.call(req).await;
+    };
+    if store.is_empty() {
+        return next.call(req).await;
+    }
+
+    let policy_rev = req
+        .app_data::<web::Data<RouterEngine>>()
+        .map(|engine| engine.policy_revision())
+        .unwrap_or_else(|| "unknown".into());
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let token = match token {
+        Some(token) if !token.is_empty() => token,
+        _ => {
+            return Err(with_context(
+                RouterError::Unauthenticated("missing bearer token".into()),
+                None,
+                Some(policy_rev),
+            )
+            .into());
+        }
+    };
+
+    match store.authenticate(token) {
+        Some(credential) => {
+            req.extensions_mut().insert(credential);
+            next.call(req).await
+        }
+        None => Err(with_context(
+            RouterError::Unauthenticated("unrecognized bearer token".into()),
+            None,
+            Some(policy_rev),
+        )
+        .into()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminScope {
+    Read,
+    Admin,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AdminCredentialStore {
+    tokens: HashMap<String, AdminScope>,
+}
+
+impl AdminCredentialStore {
+    pub fn new(tokens: HashMap<String, AdminScope>) -> Self {
+        Self { tokens }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn scope_for(&self, token: &str) -> Option<AdminScope> {
+        self.tokens
+            .iter()
+            .find(|(candidate, _)| constant_time_eq(candidate.as_bytes(), token.as_bytes()))
+            .map(|(_, scope)| *scope)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub async fn require_admin_scope(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let store = req.app_data::<web::Data<AdminCredentialStore>>().cloned();
+    let Some(store) = store else {
+        return next.call(req).await;
+    };
+    if store.is_empty() {
+        return next.call(req).await;
+    }
+
+    let policy_rev = req
+        .app_data::<web::Data<RouterEngine>>()
+        .map(|engine| engine.policy_revision())
+        .unwrap_or_else(|| "unknown".into());
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let token = match token {
+        Some(token) if !token.is_empty() => token,
+        _ => {
+            return Err(with_context(
+                RouterError::Unauthenticated("missing bearer token".into()),
+                None,
+                Some(policy_rev),
+            )
+            .into());
+        }
+    };
+
+    match store.scope_for(token) {
+        Some(AdminScope::Admin) => next.call(req).await,
+        Some(AdminScope::Read) => Err(with_context(
+            RouterError::Forbidden("admin scope required".into()),
+            None,
+            Some(policy_rev),
+        )
+        .into()),
+        None => Err(with_context(
+            RouterError::Unauthenticated("unrecognized admin token".into()),
+            None,
+            Some(policy_rev),
+        )
+        .into()),
+    }
+}
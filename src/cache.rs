@@ -1,8 +1,14 @@
+use crate::errors::RouterError;
+use crate::gossip::GossipHandle;
 use crate::types::{ApiKind, CacheStatus, PrivacyMode, RoutePlan};
 use ahash::AHasher;
+use arc_swap::ArcSwapOption;
 use chrono::{DateTime, Utc};
 use moka::future::Cache;
+use serde::{Deserialize, Serialize};
 use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -13,6 +19,7 @@ impl CacheKey {
     #[allow(clippy::too_many_arguments)]
     pub fn derive(
         policy_rev: &str,
+        catalog_rev: &str,
         alias_idx: u64,
         caps_mask: u64,
         json_mode: bool,
@@ -29,6 +36,7 @@ impl CacheKey {
     ) -> Self {
         let mut hasher = AHasher::default();
         hasher.write(policy_rev.as_bytes());
+        hasher.write(catalog_rev.as_bytes());
         hasher.write_u64(alias_idx);
         hasher.write_u64(caps_mask);
         hasher.write_u8(json_mode as u8);
@@ -59,41 +67,72 @@ struct CachedPlan {
     inserted_at: Instant,
     valid_until: Option<DateTime<Utc>>,
     route_reason: Option<String>,
+    policy_rev: String,
+    alias_idx: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachePersistSettings {
+    pub path: PathBuf,
+    pub max_items: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    key: u64,
+    plan: RoutePlan,
+    inserted_at: DateTime<Utc>,
+    valid_until: Option<DateTime<Utc>>,
+    route_reason: Option<String>,
+    policy_rev: String,
+    alias_idx: u64,
 }
 
 #[derive(Clone)]
 pub struct PlanCache {
     inner: Cache<CacheKey, CachedPlan>,
     fresh_ttl: Duration,
+    persist: Option<Arc<CachePersistSettings>>,
+    gossip: Arc<ArcSwapOption<GossipHandle>>,
 }
 
 impl PlanCache {
-    pub fn new(capacity: u64, fresh_ttl_ms: u64, stale_extension_ms: u64) -> Self {
+    pub fn new(capacity: u64, fresh_ttl_ms: u64, stale_extension_ms: u64, idle_ttl_ms: u64) -> Self {
+        Self::with_persistence(capacity, fresh_ttl_ms, stale_extension_ms, idle_ttl_ms, None)
+    }
+
+    pub fn with_persistence(
+        capacity: u64,
+        fresh_ttl_ms: u64,
+        stale_extension_ms: u64,
+        idle_ttl_ms: u64,
+        persist: Option<CachePersistSettings>,
+    ) -> Self {
         let fresh = Duration::from_millis(fresh_ttl_ms);
         let ttl = fresh + Duration::from_millis(stale_extension_ms);
-        let inner = Cache::builder()
+        let mut builder = Cache::builder()
             .max_capacity(capacity)
             .time_to_live(ttl)
-            .support_invalidation_closures()
-            .build();
+            .support_invalidation_closures();
+        if idle_ttl_ms > 0 {
+            builder = builder.time_to_idle(Duration::from_millis(idle_ttl_ms));
+        }
+        let inner = builder.build();
         Self {
             inner,
             fresh_ttl: fresh,
+            persist: persist.map(Arc::new),
+            gossip: Arc::new(ArcSwapOption::empty()),
         }
     }
 
+    pub fn attach_gossip(&self, gossip: Arc<GossipHandle>) {
+        self.gossip.store(Some(gossip));
+    }
+
     pub async fn get(&self, key: &CacheKey) -> Option<CacheHit> {
         self.inner.get(key).await.map(|entry| {
-            let now = Instant::now();
-            let mut status = CacheStatus::Hit;
-            if now.duration_since(entry.inserted_at) > self.fresh_ttl {
-                status = CacheStatus::Stale;
-            }
-            if let Some(valid_until) = entry.valid_until {
-                if valid_until <= Utc::now() {
-                    status = CacheStatus::Stale;
-                }
-            }
+            let status = self.status_for(&entry);
             CacheHit {
                 plan: entry.plan.clone(),
                 status,
@@ -102,23 +141,248 @@ impl PlanCache {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_or_compute<F>(
+        &self,
+        key: CacheKey,
+        valid_until: Option<DateTime<Utc>>,
+        policy_rev: String,
+        alias_idx: u64,
+        f: F,
+    ) -> Result<(CacheHit, bool), RouterError>
+    where
+        F: std::future::Future<Output = Result<(Arc<RoutePlan>, Option<String>), RouterError>>
+            + Send
+            + 'static,
+    {
+        let fresh_ttl = self.fresh_ttl;
+        let replaced_stale = self
+            .inner
+            .get(&key)
+            .await
+            .map(|cached| is_stale(&cached, fresh_ttl))
+            .unwrap_or(false);
+        let entry = self
+            .inner
+            .entry(key)
+            .or_try_insert_with_if(
+                async move {
+                    let (plan, route_reason) = f.await?;
+                    Ok::<CachedPlan, RouterError>(CachedPlan {
+                        plan,
+                        inserted_at: Instant::now(),
+                        valid_until,
+                        route_reason,
+                        policy_rev,
+                        alias_idx,
+                    })
+                },
+                move |cached| is_stale(cached, fresh_ttl),
+            )
+            .await
+            .map_err(|err| RouterError::Planning(err.to_string()))?;
+        let computed = entry.is_fresh();
+        let cached = entry.into_value();
+        let status = if computed && replaced_stale {
+            CacheStatus::Stale
+        } else if computed {
+            CacheStatus::Miss
+        } else {
+            self.status_for(&cached)
+        };
+        Ok((
+            CacheHit {
+                plan: cached.plan,
+                status,
+                route_reason: cached.route_reason,
+            },
+            computed,
+        ))
+    }
+
+    fn status_for(&self, entry: &CachedPlan) -> CacheStatus {
+        if is_stale(entry, self.fresh_ttl) {
+            CacheStatus::Stale
+        } else {
+            CacheStatus::Hit
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn insert(
         &self,
         key: CacheKey,
         plan: Arc<RoutePlan>,
         valid_until: Option<DateTime<Utc>>,
         route_reason: Option<String>,
+        policy_rev: String,
+        alias_idx: u64,
     ) {
         let entry = CachedPlan {
             plan,
             inserted_at: Instant::now(),
             valid_until,
             route_reason,
+            policy_rev,
+            alias_idx,
         };
         self.inner.insert(key, entry).await;
     }
 
-    pub async fn clear(&self) {
+    pub async fn clear(&self, policy_rev: &str) {
+        self.invalidate_all_raw().await;
+        if let Some(gossip) = self.gossip.load_full() {
+            gossip.broadcast_invalidate_all(policy_rev).await;
+        }
+    }
+
+    pub async fn invalidate_keys(&self, policy_rev: &str, keys: &[CacheKey]) {
+        self.invalidate_keys_raw(keys.iter().copied()).await;
+        if let Some(gossip) = self.gossip.load_full() {
+            gossip.broadcast_invalidate_keys(policy_rev, keys).await;
+        }
+    }
+
+    pub(crate) async fn invalidate_all_raw(&self) {
         self.inner.invalidate_all();
     }
+
+    pub(crate) async fn invalidate_keys_raw(&self, keys: impl Iterator<Item = CacheKey>) {
+        for key in keys {
+            self.inner.invalidate(&key).await;
+        }
+    }
+
+    pub async fn invalidate_for_policy(&self, policy_rev: &str) -> Result<(), RouterError> {
+        let target = policy_rev.to_string();
+        self.inner
+            .invalidate_entries_if(move |_, cached| cached.policy_rev == target)
+            .map_err(|err| RouterError::Planning(err.to_string()))?;
+        if let Some(gossip) = self.gossip.load_full() {
+            gossip.broadcast_invalidate_all(policy_rev).await;
+        }
+        Ok(())
+    }
+
+    pub fn invalidate_alias(&self, alias_idx: u64) -> Result<(), RouterError> {
+        self.inner
+            .invalidate_entries_if(move |_, cached| cached.alias_idx == alias_idx)
+            .map_err(|err| RouterError::Planning(err.to_string()))
+    }
+
+    pub async fn warm_start(&self) -> Result<usize, RouterError> {
+        let Some(settings) = self.persist.as_ref() else {
+            return Ok(0);
+        };
+        let bytes = match std::fs::read(&settings.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(RouterError::Io(err)),
+        };
+        let now = Utc::now();
+        let mut loaded = 0usize;
+        for entry in read_persisted_entries(&bytes)? {
+            if let Some(valid_until) = entry.valid_until {
+                if valid_until <= now {
+                    continue;
+                }
+            }
+            let age = now.signed_duration_since(entry.inserted_at);
+            let inserted_at = age
+                .to_std()
+                .ok()
+                .and_then(|age| Instant::now().checked_sub(age))
+                .unwrap_or_else(Instant::now);
+            let cached = CachedPlan {
+                plan: Arc::new(entry.plan),
+                inserted_at,
+                valid_until: entry.valid_until,
+                route_reason: entry.route_reason,
+                policy_rev: entry.policy_rev,
+                alias_idx: entry.alias_idx,
+            };
+            self.inner.insert(CacheKey(entry.key), cached).await;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    pub async fn flush_to_disk(&self) -> Result<usize, RouterError> {
+        let Some(settings) = self.persist.as_ref() else {
+            return Ok(0);
+        };
+        self.inner.run_pending_tasks().await;
+        let mut buf = Vec::new();
+        let mut written = 0u64;
+        for (key, cached) in self.inner.iter() {
+            if written >= settings.max_items {
+                break;
+            }
+            let entry = PersistedEntry {
+                key: key.0,
+                plan: (*cached.plan).clone(),
+                inserted_at: Utc::now()
+                    - chrono::Duration::from_std(Instant::now().duration_since(cached.inserted_at))
+                        .unwrap_or_else(|_| chrono::Duration::zero()),
+                valid_until: cached.valid_until,
+                route_reason: cached.route_reason.clone(),
+                policy_rev: cached.policy_rev.clone(),
+                alias_idx: cached.alias_idx,
+            };
+            write_persisted_entry(&mut buf, &entry)?;
+            written += 1;
+        }
+        if let Some(parent) = settings.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent).map_err(RouterError::Io)?;
+            }
+        }
+        let tmp_path = settings.path.with_extension("tmp");
+        std::fs::write(&tmp_path, &buf).map_err(RouterError::Io)?;
+        std::fs::rename(&tmp_path, &settings.path).map_err(RouterError::Io)?;
+        Ok(written as usize)
+    }
+}
+
+fn write_persisted_entry(buf: &mut Vec<u8>, entry: &PersistedEntry) -> Result<(), RouterError> {
+    let json = serde_json::to_vec(entry).map_err(|err| RouterError::Planning(err.to_string()))?;
+    buf.write_all(&(json.len() as u32).to_le_bytes())
+        .map_err(RouterError::Io)?;
+    buf.write_all(&json).map_err(RouterError::Io)?;
+    Ok(())
+}
+
+fn read_persisted_entries(bytes: &[u8]) -> Result<Vec<PersistedEntry>, RouterError> {
+    let mut cursor = bytes;
+    let mut entries = Vec::new();
+    let mut len_buf = [0u8; 4];
+    while !cursor.is_empty() {
+        cursor
+            .read_exact(&mut len_buf)
+            .map_err(RouterError::Io)?;
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if cursor.len() < len {
+            return Err(RouterError::Planning(
+                "plan cache persistence file truncated".into(),
+            ));
+        }
+        let (record, rest) = cursor.split_at(len);
+        let entry: PersistedEntry = serde_json::from_slice(record)
+            .map_err(|err| RouterError::Planning(format!("parse cache persistence record: {err}")))?;
+        entries.push(entry);
+        cursor = rest;
+    }
+    Ok(entries)
+}
+
+fn is_stale(entry: &CachedPlan, fresh_ttl: Duration) -> bool {
+    if Instant::now().duration_since(entry.inserted_at) > fresh_ttl {
+        return true;
+    }
+    if let Some(valid_until) = entry.valid_until {
+        if valid_until <= Utc::now() {
+            return true;
+        }
+    }
+    false
 }
@@ -0,0 +1,21 @@
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+pub async fn size_gate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+    min_size_bytes: u64,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let mut res = next.call(req).await?;
+    if let BodySize::Sized(len) = res.response().body().size() {
+        if len < min_size_bytes {
+            res.headers_mut()
+                .entry(header::CONTENT_ENCODING)
+                .or_insert_with(|| HeaderValue::from_static("identity"));
+        }
+    }
+    Ok(res)
+}
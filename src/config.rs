@@ -1,13 +1,16 @@
 use anyhow::{anyhow, Context, Result};
 use base64::Engine as _;
-use std::{env, path::PathBuf};
+use std::{collections::HashMap, env, path::PathBuf};
 
+use crate::auth::{AdminCredentialStore, AdminScope, CredentialStore, TenantCredential};
 use crate::types::{CatalogDocument, PolicyDocument};
 
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub bind_addr: String,
     pub workers: usize,
+    pub tls_cert_path: Option<PathBuf>,
+    pub tls_key_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,12 +19,26 @@ pub struct RouterConfig {
     pub overlay_dir: PathBuf,
     pub cache_ttl_ms: u64,
     pub cache_stale_ms: u64,
-    pub sticky_secret: Vec<u8>,
+    pub cache_idle_ttl_ms: u64,
+    pub sticky_keys: Vec<(u8, Vec<u8>)>,
     pub policy: PolicyDocument,
+    pub policy_path: PathBuf,
     pub catalog: CatalogDocument,
+    pub catalog_path: PathBuf,
     pub rate_limit_burst: f64,
     pub rate_limit_refill_per_sec: f64,
+    pub batch_concurrency_limit: usize,
     pub embedding: Option<EmbeddingConfig>,
+    pub health_probe: HealthProbeConfig,
+    pub cache_persist: CachePersistConfig,
+    pub credentials: CredentialStore,
+    pub admin_tokens: AdminCredentialStore,
+    pub hot_reload: HotReloadConfig,
+    pub gossip: GossipConfig,
+    pub reload_signing_public_key: Option<String>,
+    pub overlay_encryption_key: Option<String>,
+    pub cors: CorsConfig,
+    pub compression: CompressionConfig,
 }
 
 impl RouterConfig {
@@ -49,6 +66,10 @@ impl RouterConfig {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(cache_ttl_ms);
+        let cache_idle_ttl_ms = env::var("ROUTER_CACHE_IDLE_TTL_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
         let rate_limit_burst = env::var("ROUTER_PLAN_RATE_BURST")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -57,19 +78,14 @@ impl RouterConfig {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(10.0);
+        let batch_concurrency_limit = env::var("ROUTER_BATCH_CONCURRENCY_LIMIT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(16);
 
-        let sticky_secret = match env::var("ROUTER_STICKY_SECRET") {
-            Ok(value) if !value.is_empty() => {
-                let engine = base64::engine::general_purpose::STANDARD;
-                engine
-                    .decode(value)
-                    .context("decode ROUTER_STICKY_SECRET base64")?
-            }
-            _ => {
-                tracing::warn!("ROUTER_STICKY_SECRET not set; using insecure default");
-                b"labiium-router-dev-secret".to_vec()
-            }
-        };
+        let (tls_cert_path, tls_key_path) = tls_paths_from_env()?;
+
+        let sticky_keys = sticky_keys_from_env()?;
 
         let policy_json = std::fs::read_to_string(&policy_path)
             .with_context(|| format!("read policy file at {:?}", policy_path))?;
@@ -83,22 +99,294 @@ impl RouterConfig {
             .or_else(|_| serde_yaml::from_str(&catalog_json))
             .with_context(|| "parse catalog document")?;
         let embedding = embedding_from_env()?;
+        let health_probe = health_probe_from_env();
+        let credentials = credentials_from_env()?;
+        let admin_tokens = admin_tokens_from_env()?;
+        let hot_reload = hot_reload_from_env();
+        let cache_persist = cache_persist_from_env();
+        let gossip = gossip_from_env();
+        let reload_signing_public_key = env::var("ROUTER_RELOAD_SIGNING_PUBKEY")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let overlay_encryption_key = env::var("ROUTER_OVERLAY_ENCRYPTION_KEY")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let cors = cors_from_env();
+        let compression = compression_from_env();
 
         Ok(Self {
-            server: ServerConfig { bind_addr, workers },
+            server: ServerConfig {
+                bind_addr,
+                workers,
+                tls_cert_path,
+                tls_key_path,
+            },
             overlay_dir,
             cache_ttl_ms,
             cache_stale_ms,
-            sticky_secret,
+            cache_idle_ttl_ms,
+            sticky_keys,
             policy,
+            policy_path,
             catalog,
+            catalog_path,
             rate_limit_burst,
             rate_limit_refill_per_sec,
+            batch_concurrency_limit,
             embedding,
+            health_probe,
+            credentials,
+            admin_tokens,
+            hot_reload,
+            cache_persist,
+            gossip,
+            reload_signing_public_key,
+            overlay_encryption_key,
+            cors,
+            compression,
         })
     }
 }
 
+fn hot_reload_from_env() -> HotReloadConfig {
+    HotReloadConfig {
+        enabled: env_truthy("ROUTER_HOT_RELOAD_ENABLED"),
+        allow_equal_revision: env_truthy("ROUTER_HOT_RELOAD_ALLOW_EQUAL_REVISION"),
+        debounce_ms: env::var("ROUTER_HOT_RELOAD_DEBOUNCE_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HotReloadConfig {
+    pub enabled: bool,
+    pub allow_equal_revision: bool,
+    pub debounce_ms: u64,
+}
+
+fn credentials_from_env() -> Result<CredentialStore> {
+    let raw = match env::var("ROUTER_AUTH_TOKENS") {
+        Ok(value) if !value.trim().is_empty() => value,
+        _ => {
+            tracing::warn!("ROUTER_AUTH_TOKENS not set; /route/plan auth is disabled");
+            return Ok(CredentialStore::default());
+        }
+    };
+
+    let mut tokens = HashMap::new();
+    for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+        let mut parts = entry.splitn(4, ':');
+        let token = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("ROUTER_AUTH_TOKENS entry '{entry}' missing token"))?;
+        let tenant = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("ROUTER_AUTH_TOKENS entry '{entry}' missing tenant"))?;
+        let project = parts.next().filter(|s| !s.is_empty()).map(String::from);
+        let allowed_aliases = parts
+            .next()
+            .map(|aliases| aliases.split('|').map(String::from).collect())
+            .unwrap_or_default();
+        tokens.insert(
+            token.to_string(),
+            TenantCredential {
+                tenant: tenant.to_string(),
+                project,
+                allowed_aliases,
+            },
+        );
+    }
+    Ok(CredentialStore::new(tokens))
+}
+
+fn admin_tokens_from_env() -> Result<AdminCredentialStore> {
+    let raw = match env::var("ROUTER_ADMIN_TOKENS") {
+        Ok(value) if !value.trim().is_empty() => value,
+        _ => {
+            tracing::warn!("ROUTER_ADMIN_TOKENS not set; /admin endpoints are unauthenticated");
+            return Ok(AdminCredentialStore::default());
+        }
+    };
+
+    let mut tokens = HashMap::new();
+    for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+        let mut parts = entry.splitn(2, ':');
+        let token = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("ROUTER_ADMIN_TOKENS entry '{entry}' missing token"))?;
+        let scope = match parts.next().map(|s| s.trim().to_ascii_lowercase()) {
+            None => AdminScope::Admin,
+            Some(s) if s == "admin" => AdminScope::Admin,
+            Some(s) if s == "read" => AdminScope::Read,
+            Some(other) => {
+                return Err(anyhow!(
+                    "ROUTER_ADMIN_TOKENS entry '{entry}' has unknown scope '{other}'; expected 'read' or 'admin'"
+                ))
+            }
+        };
+        tokens.insert(token.to_string(), scope);
+    }
+    Ok(AdminCredentialStore::new(tokens))
+}
+
+fn tls_paths_from_env() -> Result<(Option<PathBuf>, Option<PathBuf>)> {
+    let cert = env::var("ROUTER_TLS_CERT").ok().map(PathBuf::from);
+    let key = env::var("ROUTER_TLS_KEY").ok().map(PathBuf::from);
+    match (&cert, &key) {
+        (Some(_), Some(_)) | (None, None) => Ok((cert, key)),
+        (Some(_), None) => Err(anyhow!(
+            "ROUTER_TLS_CERT is set but ROUTER_TLS_KEY is not; set both to enable TLS or neither to run plaintext"
+        )),
+        (None, Some(_)) => Err(anyhow!(
+            "ROUTER_TLS_KEY is set but ROUTER_TLS_CERT is not; set both to enable TLS or neither to run plaintext"
+        )),
+    }
+}
+
+pub fn load_tls_server_config(
+    cert_path: &std::path::Path,
+    key_path: &std::path::Path,
+) -> Result<rustls::ServerConfig> {
+    let cert_file = std::fs::File::open(cert_path)
+        .with_context(|| format!("open TLS cert file at {:?}", cert_path))?;
+    let cert_chain = rustls_pemfile::certs(&mut std::io::BufReader::new(cert_file))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("parse TLS cert chain at {:?}", cert_path))?;
+    if cert_chain.is_empty() {
+        return Err(anyhow!("no certificates found in {:?}", cert_path));
+    }
+
+    let key_file = std::fs::File::open(key_path)
+        .with_context(|| format!("open TLS key file at {:?}", key_path))?;
+    let key = rustls_pemfile::private_key(&mut std::io::BufReader::new(key_file))
+        .with_context(|| format!("parse TLS private key at {:?}", key_path))?
+        .ok_or_else(|| anyhow!("no private key found in {:?}", key_path))?;
+
+    rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .context("build rustls server config from TLS cert/key")
+}
+
+fn sticky_keys_from_env() -> Result<Vec<(u8, Vec<u8>)>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+
+    if let Ok(raw) = env::var("ROUTER_STICKY_KEYS") {
+        if !raw.trim().is_empty() {
+            let mut keys = Vec::new();
+            for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+                let (id_str, secret_str) = entry
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("ROUTER_STICKY_KEYS entry '{entry}' missing ':'"))?;
+                let id: u8 = id_str
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("invalid key id in '{entry}'"))?;
+                let secret = engine
+                    .decode(secret_str.trim())
+                    .with_context(|| format!("decode base64 secret for key id {id}"))?;
+                keys.push((id, secret));
+            }
+            if keys.is_empty() {
+                return Err(anyhow!("ROUTER_STICKY_KEYS set but no keys parsed"));
+            }
+            return Ok(keys);
+        }
+    }
+
+    let secret = match env::var("ROUTER_STICKY_SECRET") {
+        Ok(value) if !value.is_empty() => engine
+            .decode(value)
+            .context("decode ROUTER_STICKY_SECRET base64")?,
+        _ => {
+            tracing::warn!("ROUTER_STICKY_SECRET not set; using insecure default");
+            b"labiium-router-dev-secret".to_vec()
+        }
+    };
+    Ok(vec![(0, secret)])
+}
+
+fn health_probe_from_env() -> HealthProbeConfig {
+    HealthProbeConfig {
+        enabled: env_truthy("ROUTER_HEALTH_PROBE_ENABLED"),
+        interval_ms: env::var("ROUTER_HEALTH_PROBE_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30_000),
+        timeout_ms: env::var("ROUTER_HEALTH_PROBE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(2_000),
+        path: env::var("ROUTER_HEALTH_PROBE_PATH").unwrap_or_else(|_| "/healthz".into()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthProbeConfig {
+    pub enabled: bool,
+    pub interval_ms: u64,
+    pub timeout_ms: u64,
+    pub path: String,
+}
+
+fn cache_persist_from_env() -> CachePersistConfig {
+    let path = env::var("ROUTER_CACHE_PERSIST_PATH").ok().map(PathBuf::from);
+    CachePersistConfig {
+        enabled: path.is_some(),
+        path: path.unwrap_or_else(|| PathBuf::from("./data/plan_cache.bin")),
+        max_items: env::var("ROUTER_CACHE_PERSIST_MAX_ITEMS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10_000),
+        interval_ms: env::var("ROUTER_CACHE_PERSIST_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60_000),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachePersistConfig {
+    pub enabled: bool,
+    pub path: PathBuf,
+    pub max_items: u64,
+    pub interval_ms: u64,
+}
+
+fn gossip_from_env() -> GossipConfig {
+    let peers: Vec<String> = env::var("ROUTER_GOSSIP_PEERS")
+        .ok()
+        .map(|raw| {
+            raw.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    GossipConfig {
+        enabled: !peers.is_empty(),
+        bind_addr: env::var("ROUTER_GOSSIP_BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:7946".into()),
+        peers,
+        fanout: env::var("ROUTER_GOSSIP_FANOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GossipConfig {
+    pub enabled: bool,
+    pub bind_addr: String,
+    pub peers: Vec<String>,
+    pub fanout: usize,
+}
+
 fn embedding_from_env() -> Result<Option<EmbeddingConfig>> {
     if !env_truthy("ROUTER_EMBEDDINGS_ENABLED") {
         return Ok(None);
@@ -140,14 +428,186 @@ fn embedding_from_env() -> Result<Option<EmbeddingConfig>> {
         }
     };
 
+    let hnsw_m = env::var("ROUTER_EMBEDDINGS_HNSW_M")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(16)
+        .max(2);
+    let hnsw_ef_construction = env::var("ROUTER_EMBEDDINGS_HNSW_EF_CONSTRUCTION")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(200)
+        .max(hnsw_m);
+    let hnsw_ef_search = env::var("ROUTER_EMBEDDINGS_HNSW_EF_SEARCH")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(64)
+        .max(top_k);
+    let hnsw_min_tasks = env::var("ROUTER_EMBEDDINGS_HNSW_MIN_TASKS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(512);
+    let mmr_lambda = env::var("ROUTER_EMBEDDINGS_MMR_LAMBDA")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(0.7)
+        .clamp(0.0, 1.0);
+    let quantize = env_truthy("ROUTER_EMBEDDINGS_QUANTIZED");
+
     Ok(Some(EmbeddingConfig {
         canonical_path,
         top_k,
         cache_ttl_ms,
         provider,
+        hnsw_m,
+        hnsw_ef_construction,
+        hnsw_ef_search,
+        hnsw_min_tasks,
+        mmr_lambda,
+        quantize,
     }))
 }
 
+const DEFAULT_CORS_EXPOSED_HEADERS: &[&str] = &[
+    "Router-Schema",
+    "Router-Latency",
+    "Config-Revision",
+    "Catalog-Revision",
+    "X-Route-Cache",
+    "X-Resolved-Model",
+    "X-Route-Id",
+    "X-Policy-Rev",
+    "X-Request-Id",
+    "X-Route-Tier",
+    "X-Route-Provider",
+    "X-Route-Why",
+    "X-Content-Used",
+    "ETag",
+    "X-Catalog-Weak",
+    "X-Catalog-Revision",
+];
+
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub exposed_headers: Vec<String>,
+    pub max_age_secs: usize,
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".into(), "POST".into(), "PUT".into()],
+            allowed_headers: vec![
+                "content-type".into(),
+                "accept".into(),
+                "authorization".into(),
+            ],
+            exposed_headers: DEFAULT_CORS_EXPOSED_HEADERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            max_age_secs: 3600,
+            allow_credentials: false,
+        }
+    }
+}
+
+fn cors_from_env() -> CorsConfig {
+    let defaults = CorsConfig::default();
+    let allowed_origins = env_list("ROUTER_CORS_ALLOWED_ORIGINS");
+    let allowed_methods = {
+        let configured = env_list("ROUTER_CORS_ALLOWED_METHODS");
+        if configured.is_empty() {
+            defaults.allowed_methods
+        } else {
+            configured
+        }
+    };
+    let allowed_headers = {
+        let configured = env_list("ROUTER_CORS_ALLOWED_HEADERS");
+        if configured.is_empty() {
+            defaults.allowed_headers
+        } else {
+            configured
+        }
+    };
+    let exposed_headers = {
+        let configured = env_list("ROUTER_CORS_EXPOSED_HEADERS");
+        if configured.is_empty() {
+            defaults.exposed_headers
+        } else {
+            configured
+        }
+    };
+    let max_age_secs = env::var("ROUTER_CORS_MAX_AGE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults.max_age_secs);
+    let allow_credentials = env_truthy("ROUTER_CORS_ALLOW_CREDENTIALS");
+
+    if allow_credentials && allowed_origins.is_empty() {
+        tracing::warn!(
+            "ROUTER_CORS_ALLOW_CREDENTIALS is set but ROUTER_CORS_ALLOWED_ORIGINS is empty; \
+             credentials require a specific origin allowlist, ignoring credentials flag"
+        );
+    }
+
+    CorsConfig {
+        allowed_origins,
+        allowed_methods,
+        allowed_headers,
+        exposed_headers,
+        max_age_secs,
+        allow_credentials,
+    }
+}
+
+fn env_list(key: &str) -> Vec<String> {
+    env::var(key)
+        .ok()
+        .map(|raw| {
+            raw.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub min_size_bytes: u64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_size_bytes: 256,
+        }
+    }
+}
+
+fn compression_from_env() -> CompressionConfig {
+    let defaults = CompressionConfig::default();
+    CompressionConfig {
+        enabled: env::var("ROUTER_COMPRESSION_ENABLED")
+            .ok()
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(defaults.enabled),
+        min_size_bytes: env::var("ROUTER_COMPRESSION_MIN_SIZE_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.min_size_bytes),
+    }
+}
+
 fn env_truthy(key: &str) -> bool {
     env::var(key)
         .ok()
@@ -166,6 +626,12 @@ pub struct EmbeddingConfig {
     pub top_k: usize,
     pub cache_ttl_ms: u64,
     pub provider: EmbeddingProviderKind,
+    pub hnsw_m: usize,
+    pub hnsw_ef_construction: usize,
+    pub hnsw_ef_search: usize,
+    pub hnsw_min_tasks: usize,
+    pub mmr_lambda: f32,
+    pub quantize: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -8,7 +8,8 @@ use moka::future::Cache;
 use parking_lot::Mutex;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::hash::Hasher;
 use std::path::Path;
 use std::str::FromStr;
@@ -29,8 +30,9 @@ pub struct CanonicalSelection {
 pub struct EmbeddingRuntime {
     router: EmbeddingRouter,
     provider: Arc<dyn EmbeddingBackend>,
-    cache: Cache<u64, Arc<Vec<f32>>>,
+    cache: Cache<u64, Arc<CachedEmbedding>>,
     top_k: usize,
+    quantize: bool,
 }
 
 impl EmbeddingRuntime {
@@ -42,17 +44,18 @@ impl EmbeddingRuntime {
             EmbeddingProviderKind::Hashed => Arc::new(HashingBackend),
         };
 
-        let tasks = load_canonical_tasks(&cfg.canonical_path, provider.clone()).await?;
+        let tasks = load_canonical_tasks(&cfg.canonical_path, provider.clone(), cfg.quantize).await?;
         let cache = Cache::builder()
             .max_capacity(2048)
             .time_to_live(Duration::from_millis(cfg.cache_ttl_ms))
             .build();
 
         Ok(Self {
-            router: EmbeddingRouter::new(tasks),
+            router: EmbeddingRouter::new(tasks, cfg),
             provider,
             cache,
             top_k: cfg.top_k,
+            quantize: cfg.quantize,
         })
     }
 
@@ -65,81 +68,478 @@ impl EmbeddingRuntime {
             None => return Ok(None),
         };
         let text_hash = hash_text(&text);
-        let embedding = if let Some(hit) = self.cache.get(&text_hash).await {
+        let cached = if let Some(hit) = self.cache.get(&text_hash).await {
             hit
         } else {
             let vectors = self.provider.embed(std::slice::from_ref(&text)).await?;
             if vectors.is_empty() {
                 return Ok(None);
             }
-            let vec = Arc::new(normalize(vectors.into_iter().next().unwrap()));
-            self.cache.insert(text_hash, vec.clone()).await;
-            vec
+            let vec = normalize(vectors.into_iter().next().unwrap());
+            let cached = Arc::new(if self.quantize {
+                CachedEmbedding::Quantized(QuantizedVector::quantize(&vec))
+            } else {
+                CachedEmbedding::Dense(vec)
+            });
+            self.cache.insert(text_hash, cached.clone()).await;
+            cached
         };
 
-        Ok(self.router.select(&embedding, self.top_k))
+        let dense_query = cached.to_dense();
+        let quantized_query = match cached.as_ref() {
+            CachedEmbedding::Quantized(q) => Some(q),
+            CachedEmbedding::Dense(_) => None,
+        };
+        Ok(self
+            .router
+            .select(&dense_query, quantized_query, self.top_k))
+    }
+}
+
+#[derive(Debug)]
+enum CachedEmbedding {
+    Dense(Vec<f32>),
+    Quantized(QuantizedVector),
+}
+
+impl CachedEmbedding {
+    fn to_dense(&self) -> Vec<f32> {
+        match self {
+            CachedEmbedding::Dense(vec) => vec.clone(),
+            CachedEmbedding::Quantized(q) => q.dequantize(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QuantizedVector {
+    codes: Vec<i8>,
+    scale: f32,
+    min: f32,
+    code_sum: i32,
+}
+
+impl QuantizedVector {
+    fn quantize(vec: &[f32]) -> Self {
+        let min = vec.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = vec.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let range = (max - min).max(f32::EPSILON);
+        let scale = range / 255.0;
+        let mut code_sum = 0i32;
+        let codes = vec
+            .iter()
+            .map(|&v| {
+                let unsigned = (((v - min) / range) * 255.0).round().clamp(0.0, 255.0) as i32;
+                code_sum += unsigned;
+                (unsigned - 128) as i8
+            })
+            .collect();
+        Self {
+            codes,
+            scale,
+            min,
+            code_sum,
+        }
+    }
+
+    fn dequantize(&self) -> Vec<f32> {
+        self.codes
+            .iter()
+            .map(|&code| self.min + self.scale * (code as i32 + 128) as f32)
+            .collect()
     }
 }
 
+fn quantized_dot(a: &QuantizedVector, b: &QuantizedVector) -> f32 {
+    let n = a.codes.len() as f32;
+    let cross: i32 = a
+        .codes
+        .iter()
+        .zip(b.codes.iter())
+        .map(|(&qa, &qb)| (qa as i32 + 128) * (qb as i32 + 128))
+        .sum();
+    n * a.min * b.min
+        + a.min * b.scale * b.code_sum as f32
+        + b.min * a.scale * a.code_sum as f32
+        + a.scale * b.scale * cross as f32
+}
+
 #[derive(Debug)]
 struct CanonicalTask {
     id: String,
     preferred_model: String,
     weight: f32,
     embedding: Vec<f32>,
+    quantized: Option<QuantizedVector>,
+}
+
+#[derive(Debug)]
+enum RouterIndex {
+    Linear,
+    Hnsw(HnswIndex),
 }
 
 #[derive(Debug)]
 struct EmbeddingRouter {
     tasks: Vec<CanonicalTask>,
+    index: RouterIndex,
+    ef_search: usize,
+    mmr_lambda: f32,
 }
 
 impl EmbeddingRouter {
-    fn new(tasks: Vec<CanonicalTask>) -> Self {
-        Self { tasks }
+    fn new(tasks: Vec<CanonicalTask>, cfg: &EmbeddingConfig) -> Self {
+        let index = if tasks.len() >= cfg.hnsw_min_tasks {
+            HnswIndex::build(&tasks, cfg.hnsw_m, cfg.hnsw_ef_construction)
+                .map(RouterIndex::Hnsw)
+                .unwrap_or(RouterIndex::Linear)
+        } else {
+            RouterIndex::Linear
+        };
+        Self {
+            tasks,
+            index,
+            ef_search: cfg.hnsw_ef_search,
+            mmr_lambda: cfg.mmr_lambda,
+        }
     }
 
-    fn select(&self, query: &[f32], k: usize) -> Option<CanonicalSelection> {
+    fn select(
+        &self,
+        query: &[f32],
+        query_q: Option<&QuantizedVector>,
+        k: usize,
+    ) -> Option<CanonicalSelection> {
         if self.tasks.is_empty() {
             return None;
         }
-        let mut scored: Vec<(f32, &CanonicalTask)> = self
-            .tasks
+        let k = k.max(1);
+        // Pull a wider candidate pool than `k` so the MMR pass below has
+        // near-duplicates to trade off against, not just the final set.
+        let pool_size = (k * 4).max(self.ef_search);
+        let pool: Vec<(f32, &CanonicalTask)> = match &self.index {
+            RouterIndex::Linear => {
+                let mut scored: Vec<(f32, &CanonicalTask)> = self
+                    .tasks
+                    .iter()
+                    .map(|task| (weighted_score(task, query, query_q), task))
+                    .collect();
+                scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+                scored.truncate(pool_size);
+                scored
+            }
+            RouterIndex::Hnsw(index) => index
+                .search(query, pool_size, pool_size)
+                .into_iter()
+                .map(|node_id| {
+                    let task = &self.tasks[node_id as usize];
+                    (weighted_score(task, query, query_q), task)
+                })
+                .collect(),
+        };
+        let candidates = mmr_select(pool, k, self.mmr_lambda);
+        aggregate(candidates, k)
+    }
+}
+
+fn weighted_score(task: &CanonicalTask, query: &[f32], query_q: Option<&QuantizedVector>) -> f32 {
+    let sim = match (query_q, task.quantized.as_ref()) {
+        (Some(query_q), Some(task_q)) => quantized_dot(query_q, task_q),
+        _ => dot(&task.embedding, query),
+    };
+    let weight = if task.weight <= 0.0 { 1.0 } else { task.weight };
+    sim * weight
+}
+
+fn mmr_select(pool: Vec<(f32, &CanonicalTask)>, k: usize, lambda: f32) -> Vec<(f32, &CanonicalTask)> {
+    let mut remaining = pool;
+    let mut selected: Vec<(f32, &CanonicalTask)> = Vec::with_capacity(k);
+    while selected.len() < k && !remaining.is_empty() {
+        let best_idx = remaining
             .iter()
-            .map(|task| {
-                let sim = dot(&task.embedding, query);
-                let weight = if task.weight <= 0.0 { 1.0 } else { task.weight };
-                (sim * weight, task)
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                mmr_objective(a, &selected, lambda)
+                    .partial_cmp(&mmr_objective(b, &selected, lambda))
+                    .unwrap_or(std::cmp::Ordering::Equal)
             })
-            .collect();
-        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-        let mut aggregated: HashMap<&str, (f32, Vec<String>)> = HashMap::new();
-        for (score, task) in scored.into_iter().take(k.max(1)) {
-            if score <= 0.0 {
+            .map(|(idx, _)| idx)
+            .expect("remaining is non-empty");
+        selected.push(remaining.remove(best_idx));
+    }
+    selected
+}
+
+fn mmr_objective(
+    candidate: &(f32, &CanonicalTask),
+    selected: &[(f32, &CanonicalTask)],
+    lambda: f32,
+) -> f32 {
+    let (relevance, task) = candidate;
+    let redundancy = selected
+        .iter()
+        .map(|(_, other)| task_similarity(task, other))
+        .fold(f32::MIN, f32::max);
+    let redundancy = if selected.is_empty() { 0.0 } else { redundancy };
+    lambda * relevance - (1.0 - lambda) * redundancy
+}
+
+fn task_similarity(a: &CanonicalTask, b: &CanonicalTask) -> f32 {
+    match (a.quantized.as_ref(), b.quantized.as_ref()) {
+        (Some(a_q), Some(b_q)) => quantized_dot(a_q, b_q),
+        _ => dot(&a.embedding, &b.embedding),
+    }
+}
+
+fn aggregate(candidates: Vec<(f32, &CanonicalTask)>, k: usize) -> Option<CanonicalSelection> {
+    let mut aggregated: HashMap<&str, (f32, Vec<String>)> = HashMap::new();
+    for (score, task) in candidates {
+        if score <= 0.0 {
+            continue;
+        }
+        aggregated
+            .entry(&task.preferred_model)
+            .and_modify(|entry| {
+                entry.0 += score;
+                entry.1.push(task.id.clone());
+            })
+            .or_insert_with(|| (score, vec![task.id.clone()]));
+    }
+    let (model_id, (score_sum, ids)) = aggregated.into_iter().max_by(|a, b| {
+        a.1 .0
+            .partial_cmp(&b.1 .0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })?;
+    let normalized = (score_sum / k.max(1) as f32).min(1.0);
+    if normalized < MIN_CANONICAL_SCORE {
+        return None;
+    }
+    Some(CanonicalSelection {
+        model_id: model_id.to_string(),
+        canonical_ids: ids,
+        score: normalized,
+    })
+}
+
+#[derive(Debug)]
+struct HnswIndex {
+    nodes: Vec<Vec<f32>>,
+    layers: Vec<HashMap<u32, Vec<u32>>>,
+    entry_point: u32,
+    top_layer: usize,
+    m: usize,
+    m_max0: usize,
+    ef_construction: usize,
+    level_norm: f64,
+}
+
+impl HnswIndex {
+    fn build(tasks: &[CanonicalTask], m: usize, ef_construction: usize) -> Option<Self> {
+        let nodes: Vec<Vec<f32>> = tasks.iter().map(|t| t.embedding.clone()).collect();
+        if nodes.is_empty() {
+            return None;
+        }
+        let mut index = Self {
+            nodes,
+            layers: vec![HashMap::new()],
+            entry_point: 0,
+            top_layer: 0,
+            m,
+            m_max0: m * 2,
+            ef_construction,
+            level_norm: 1.0 / (m as f64).ln(),
+        };
+        let mut rng = LevelRng::new(0x4242_1337_u64 ^ index.nodes.len() as u64);
+        for id in 0..index.nodes.len() as u32 {
+            index.insert(id, &mut rng);
+        }
+        Some(index)
+    }
+
+    fn distance_to(&self, node: u32, query: &[f32]) -> f32 {
+        1.0 - dot(&self.nodes[node as usize], query)
+    }
+
+    fn random_level(&self, rng: &mut LevelRng) -> usize {
+        let uniform = rng.next_f64().max(f64::MIN_POSITIVE);
+        (-uniform.ln() * self.level_norm).floor() as usize
+    }
+
+    fn ensure_layer(&mut self, layer: usize) {
+        while self.layers.len() <= layer {
+            self.layers.push(HashMap::new());
+        }
+    }
+
+    fn insert(&mut self, id: u32, rng: &mut LevelRng) {
+        let level = self.random_level(rng);
+        self.ensure_layer(level);
+        if id == 0 {
+            self.entry_point = id;
+            self.top_layer = level;
+            for layer in self.layers.iter_mut().take(level + 1) {
+                layer.insert(id, Vec::new());
+            }
+            return;
+        }
+
+        let query = self.nodes[id as usize].clone();
+        let mut curr = self.entry_point;
+        for layer in (level + 1..=self.top_layer).rev() {
+            curr = self.greedy_closest(&query, curr, layer);
+        }
+
+        for layer in (0..=level.min(self.top_layer)).rev() {
+            let candidates = self.search_layer(&query, curr, self.ef_construction, layer);
+            let cap = if layer == 0 { self.m_max0 } else { self.m };
+            let neighbors: Vec<u32> = candidates.iter().take(cap).map(|&(_, n)| n).collect();
+            self.layers[layer].insert(id, neighbors.clone());
+            for &neighbor in &neighbors {
+                let neighbor_vec = self.nodes[neighbor as usize].clone();
+                let mut entry = self.layers[layer].remove(&neighbor).unwrap_or_default();
+                entry.push(id);
+                if entry.len() > cap {
+                    entry.sort_by(|&a, &b| {
+                        self.distance_to(a, &neighbor_vec)
+                            .partial_cmp(&self.distance_to(b, &neighbor_vec))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                    entry.truncate(cap);
+                }
+                self.layers[layer].insert(neighbor, entry);
+            }
+            if let Some(&(_, nearest)) = candidates.first() {
+                curr = nearest;
+            }
+        }
+
+        if level > self.top_layer {
+            self.top_layer = level;
+            self.entry_point = id;
+        }
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut curr = start;
+        let mut curr_dist = self.distance_to(curr, query);
+        loop {
+            let mut moved = false;
+            if let Some(neighbors) = self.layers[layer].get(&curr) {
+                for &candidate in neighbors {
+                    let dist = self.distance_to(candidate, query);
+                    if dist < curr_dist {
+                        curr_dist = dist;
+                        curr = candidate;
+                        moved = true;
+                    }
+                }
+            }
+            if !moved {
+                return curr;
+            }
+        }
+    }
+
+    fn search_layer(&self, query: &[f32], entry: u32, ef: usize, layer: usize) -> Vec<(f32, u32)> {
+        let mut visited: HashSet<u32> = HashSet::new();
+        visited.insert(entry);
+        let entry_dist = self.distance_to(entry, query);
+
+        let mut frontier: BinaryHeap<Reverse<HeapNode>> = BinaryHeap::new();
+        frontier.push(Reverse(HeapNode {
+            dist: entry_dist,
+            id: entry,
+        }));
+        let mut found: BinaryHeap<HeapNode> = BinaryHeap::new();
+        found.push(HeapNode {
+            dist: entry_dist,
+            id: entry,
+        });
+
+        while let Some(Reverse(current)) = frontier.pop() {
+            let worst = found.peek().map(|h| h.dist).unwrap_or(f32::INFINITY);
+            if current.dist > worst && found.len() >= ef {
+                break;
+            }
+            let Some(neighbors) = self.layers[layer].get(&current.id) else {
                 continue;
+            };
+            for &neighbor in neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let dist = self.distance_to(neighbor, query);
+                let worst = found.peek().map(|h| h.dist).unwrap_or(f32::INFINITY);
+                if found.len() < ef || dist < worst {
+                    frontier.push(Reverse(HeapNode { dist, id: neighbor }));
+                    found.push(HeapNode { dist, id: neighbor });
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
             }
-            aggregated
-                .entry(&task.preferred_model)
-                .and_modify(|entry| {
-                    entry.0 += score;
-                    entry.1.push(task.id.clone());
-                })
-                .or_insert_with(|| (score, vec![task.id.clone()]));
         }
-        let (model_id, (score_sum, ids)) = aggregated.into_iter().max_by(|a, b| {
-            a.1 .0
-                .partial_cmp(&b.1 .0)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        })?;
-        let normalized = (score_sum / k.max(1) as f32).min(1.0);
-        if normalized < MIN_CANONICAL_SCORE {
-            return None;
+
+        let mut results: Vec<(f32, u32)> = found.into_iter().map(|h| (h.dist, h.id)).collect();
+        results.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        results
+    }
+
+    fn search(&self, query: &[f32], ef: usize, k: usize) -> Vec<u32> {
+        let mut curr = self.entry_point;
+        for layer in (1..=self.top_layer).rev() {
+            curr = self.greedy_closest(query, curr, layer);
         }
-        Some(CanonicalSelection {
-            model_id: model_id.to_string(),
-            canonical_ids: ids,
-            score: normalized,
-        })
+        self.search_layer(query, curr, ef.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|(_, id)| id)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct HeapNode {
+    dist: f32,
+    id: u32,
+}
+
+impl PartialEq for HeapNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+impl Eq for HeapNode {}
+impl PartialOrd for HeapNode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+struct LevelRng(u64);
+
+impl LevelRng {
+    fn new(seed: u64) -> Self {
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
     }
 }
 
@@ -161,6 +561,7 @@ fn default_weight() -> f32 {
 async fn load_canonical_tasks(
     path: &Path,
     provider: Arc<dyn EmbeddingBackend>,
+    quantize: bool,
 ) -> Result<Vec<CanonicalTask>, RouterError> {
     let raw = std::fs::read_to_string(path)?;
     let configs: Vec<CanonicalTaskConfig> =
@@ -180,11 +581,16 @@ async fn load_canonical_tasks(
     let tasks = configs
         .into_iter()
         .zip(vectors)
-        .map(|(cfg, vector)| CanonicalTask {
-            id: cfg.id,
-            preferred_model: cfg.preferred_model,
-            weight: cfg.weight.max(0.1),
-            embedding: normalize(vector),
+        .map(|(cfg, vector)| {
+            let embedding = normalize(vector);
+            let quantized = quantize.then(|| QuantizedVector::quantize(&embedding));
+            CanonicalTask {
+                id: cfg.id,
+                preferred_model: cfg.preferred_model,
+                weight: cfg.weight.max(0.1),
+                embedding,
+                quantized,
+            }
         })
         .collect();
     Ok(tasks)
@@ -344,3 +750,59 @@ pub fn canonical_hash(selection: &CanonicalSelection) -> u64 {
     }
     hasher.finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic xorshift so test fixtures don't depend on rand's output format.
+    fn next_f32(state: &mut u64) -> f32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        ((*state >> 11) as f32 / (1u64 << 53) as f32) * 2.0 - 1.0
+    }
+
+    fn random_vector(state: &mut u64, dims: usize) -> Vec<f32> {
+        let vec: Vec<f32> = (0..dims).map(|_| next_f32(state)).collect();
+        normalize(vec)
+    }
+
+    fn linear_topk(tasks: &[CanonicalTask], query: &[f32], k: usize) -> Vec<u32> {
+        let mut scored: Vec<(f32, u32)> = tasks
+            .iter()
+            .enumerate()
+            .map(|(idx, task)| (dot(&task.embedding, query), idx as u32))
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.into_iter().take(k).map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn hnsw_search_matches_linear_scan_recall() {
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let dims = 16;
+        let tasks: Vec<CanonicalTask> = (0..500)
+            .map(|i| CanonicalTask {
+                id: format!("task-{i}"),
+                preferred_model: "model-a".into(),
+                weight: 1.0,
+                embedding: random_vector(&mut state, dims),
+                quantized: None,
+            })
+            .collect();
+        let index = HnswIndex::build(&tasks, 16, 128).expect("non-empty task set builds an index");
+
+        let k = 10;
+        let queries = 20;
+        let mut hits = 0usize;
+        for _ in 0..queries {
+            let query = random_vector(&mut state, dims);
+            let expected = linear_topk(&tasks, &query, k);
+            let got = index.search(&query, 128, k);
+            hits += got.iter().filter(|id| expected.contains(id)).count();
+        }
+        let recall = hits as f32 / (queries * k) as f32;
+        assert!(recall >= 0.9, "HNSW recall@{k} too low vs linear scan: {recall}");
+    }
+}
@@ -1,24 +1,33 @@
-use crate::cache::{CacheKey, PlanCache};
-use crate::config::RouterConfig;
+use crate::cache::{CacheKey, CachePersistSettings, PlanCache};
+use crate::config::{CachePersistConfig, GossipConfig, HealthProbeConfig, HotReloadConfig, RouterConfig};
 use crate::embedding::{canonical_hash, CanonicalSelection, EmbeddingRuntime};
 use crate::errors::RouterError;
-use crate::health::{HealthStats, HealthStore};
+use crate::gossip::GossipHandle;
+use crate::health::{EjectionState, HealthStats, HealthStore};
 use crate::rate::RateLimiter;
 use crate::stickiness::{StickinessClaims, StickinessManager};
 use crate::types::*;
 use arc_swap::ArcSwap;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{Duration as ChronoDuration, Utc};
 use dashmap::DashMap;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use futures_util::StreamExt;
+use notify::Watcher;
 use regex::Regex;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
 use uuid::Uuid;
 
 const MAX_CACHE_CAPACITY: u64 = 100_000;
 const DEFAULT_PROMPT_TOKENS: u32 = 512;
 const DEFAULT_OUTPUT_TOKENS: u32 = 256;
+const BYTES_PER_TOKEN_ESTIMATE: f32 = 4.0;
 
 bitflags::bitflags! {
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,6 +63,7 @@ struct CompiledModel {
     capabilities: CapabilityFlags,
     regions: RegionMask,
     context_tokens: u32,
+    cache_window_tokens: u32,
     prices: ModelPrice,
     target_latency_ms: u32,
     base_latency_ms: u32,
@@ -102,11 +112,36 @@ struct OverlayStore {
     content: HashMap<String, String>,
 }
 
+const LATENCY_BUCKETS_MS: [f64; 8] = [25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0];
+
+#[derive(Debug, Default, Clone)]
+struct LatencyHistogram {
+    buckets: [u64; LATENCY_BUCKETS_MS.len()],
+    sum_ms: f64,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, latency_ms: f64) {
+        for (bound, bucket) in LATENCY_BUCKETS_MS.iter().zip(self.buckets.iter_mut()) {
+            if latency_ms <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum_ms += latency_ms;
+        self.count += 1;
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct RouterMetrics {
     total_requests: Arc<DashMap<String, u64>>, // alias -> count
     model_share: Arc<DashMap<String, u64>>,    // model -> count
     cache_hits: Arc<DashMap<String, u64>>,     // alias -> hits
+    cache_misses: Arc<DashMap<String, u64>>,   // alias -> misses
+    cache_stale: Arc<DashMap<String, u64>>,    // alias -> stale hits
+    route_reasons: Arc<DashMap<String, u64>>,  // reason -> count
+    latency_histograms: Arc<DashMap<String, LatencyHistogram>>, // model -> histogram
 }
 
 pub struct RouterEngine {
@@ -120,7 +155,12 @@ pub struct RouterEngine {
     health: HealthStore,
     metrics: RouterMetrics,
     rate_limiter: RateLimiter,
+    batch_concurrency_limit: usize,
     embedding: Option<Arc<EmbeddingRuntime>>,
+    allow_equal_revision: bool,
+    http_client: reqwest::Client,
+    reload_signing_key: Option<VerifyingKey>,
+    overlay_encryption_key: Option<[u8; 32]>,
 }
 
 pub struct PlanOutcome {
@@ -131,13 +171,52 @@ pub struct PlanOutcome {
     pub route_reason: Option<String>,
 }
 
+struct BatchPreamble {
+    req: RouteRequest,
+    sticky_claims: Option<StickinessClaims>,
+    cache_key: CacheKey,
+    valid_until: Option<chrono::DateTime<Utc>>,
+    alias_idx: u64,
+    alias: CompiledAlias,
+    canonical: Option<CanonicalSelection>,
+    forced_tag: Option<String>,
+    prompt_overlays: PromptOverlays,
+    freeze_key: String,
+    content_used: ContentLevel,
+    base_reason: Option<String>,
+    caps_mask: CapabilityFlags,
+    in_tokens: u32,
+    out_tokens: u32,
+    region_mask: RegionMask,
+    boost: bool,
+}
+
 impl RouterEngine {
     pub async fn bootstrap(cfg: &RouterConfig) -> Result<Self, RouterError> {
         let compiled_catalog = compile_catalog(&cfg.catalog)?;
         let compiled_policy = compile_policy(&cfg.policy, &compiled_catalog)?;
-        let overlays = load_overlays(&cfg.overlay_dir)?;
+        let overlay_encryption_key = match cfg.overlay_encryption_key.as_deref() {
+            Some(hex_key) => Some(parse_overlay_encryption_key(hex_key)?),
+            None => None,
+        };
+        let overlays = load_overlays(&cfg.overlay_dir, overlay_encryption_key.as_ref())?;
 
-        let cache = PlanCache::new(MAX_CACHE_CAPACITY, cfg.cache_ttl_ms, cfg.cache_stale_ms);
+        let persist = cfg.cache_persist.enabled.then(|| CachePersistSettings {
+            path: cfg.cache_persist.path.clone(),
+            max_items: cfg.cache_persist.max_items,
+        });
+        let cache = PlanCache::with_persistence(
+            MAX_CACHE_CAPACITY,
+            cfg.cache_ttl_ms,
+            cfg.cache_stale_ms,
+            cfg.cache_idle_ttl_ms,
+            persist,
+        );
+        match cache.warm_start().await {
+            Ok(loaded) if loaded > 0 => tracing::info!(loaded, "plan cache warm-started from disk"),
+            Ok(_) => {}
+            Err(err) => tracing::warn!("plan cache warm-start failed: {err}"),
+        }
         let policy = ArcSwap::from_pointee(compiled_policy);
         let catalog = ArcSwap::from_pointee(compiled_catalog);
         let overlays = ArcSwap::from_pointee(overlays);
@@ -147,6 +226,11 @@ impl RouterEngine {
             None => None,
         };
 
+        let reload_signing_key = match cfg.reload_signing_public_key.as_deref() {
+            Some(hex_key) => Some(parse_signing_public_key(hex_key)?),
+            None => None,
+        };
+
         Ok(Self {
             overlay_dir: cfg.overlay_dir.to_string_lossy().into_owned(),
             cache,
@@ -154,11 +238,16 @@ impl RouterEngine {
             policy,
             catalog,
             overlays,
-            stickiness: StickinessManager::new(cfg.sticky_secret.clone()),
+            stickiness: StickinessManager::with_keys(cfg.sticky_keys.clone()),
             health: HealthStore::new(),
             metrics: RouterMetrics::default(),
             rate_limiter: RateLimiter::new(cfg.rate_limit_burst, cfg.rate_limit_refill_per_sec),
+            batch_concurrency_limit: cfg.batch_concurrency_limit.max(1),
             embedding,
+            allow_equal_revision: cfg.hot_reload.allow_equal_revision,
+            http_client: reqwest::Client::builder().build().unwrap_or_default(),
+            reload_signing_key,
+            overlay_encryption_key,
         })
     }
 
@@ -166,6 +255,10 @@ impl RouterEngine {
         &self.health
     }
 
+    pub fn http_client(&self) -> reqwest::Client {
+        self.http_client.clone()
+    }
+
     pub fn check_rate_limit(&self, key: &str) -> Result<(), RouterError> {
         if self.rate_limiter.check(key) {
             Ok(())
@@ -176,31 +269,103 @@ impl RouterEngine {
         }
     }
 
-    pub async fn reload_policy(&self, doc: PolicyDocument) -> Result<(), RouterError> {
+    pub async fn reload_policy(&self, mut doc: PolicyDocument) -> Result<(), RouterError> {
+        let current = self.policy.load();
+        let integrity = doc.integrity.take();
+        let digest = canonical_digest(&doc)?;
+        verify_integrity(
+            "policy",
+            &digest,
+            integrity.as_ref(),
+            self.reload_signing_key.as_ref(),
+        )?;
+        if !revision_advances(
+            base_revision(&current.doc.revision),
+            &doc.revision,
+            self.allow_equal_revision,
+        ) {
+            return Err(RouterError::InvalidRequest(format!(
+                "policy revision '{}' does not advance current revision '{}'",
+                doc.revision, current.doc.revision
+            )));
+        }
+        doc.revision = format!("{}@sha256:{digest}", doc.revision);
         let compiled = compile_policy(&doc, &self.catalog.load())?;
+        tracing::info!(
+            from = %current.doc.revision,
+            to = %doc.revision,
+            "policy reload accepted"
+        );
+        let superseded_revision = current.doc.revision.clone();
         self.policy.store(Arc::new(compiled));
-        self.cache.clear().await;
+        self.cache.invalidate_for_policy(&superseded_revision).await?;
         Ok(())
     }
 
-    pub async fn reload_catalog(&self, doc: CatalogDocument) -> Result<(), RouterError> {
+    pub async fn reload_catalog(&self, mut doc: CatalogDocument) -> Result<(), RouterError> {
+        let current = self.catalog.load();
+        let integrity = doc.integrity.take();
+        let digest = canonical_digest(&doc)?;
+        verify_integrity(
+            "catalog",
+            &digest,
+            integrity.as_ref(),
+            self.reload_signing_key.as_ref(),
+        )?;
+        if !revision_advances(
+            base_revision(&current.revision),
+            &doc.revision,
+            self.allow_equal_revision,
+        ) {
+            return Err(RouterError::InvalidRequest(format!(
+                "catalog revision '{}' does not advance current revision '{}'",
+                doc.revision, current.revision
+            )));
+        }
+        doc.revision = format!("{}@sha256:{digest}", doc.revision);
         let compiled = compile_catalog(&doc)?;
+        tracing::info!(
+            from = %current.revision,
+            to = %doc.revision,
+            "catalog reload accepted"
+        );
         self.catalog.store(Arc::new(compiled));
         let compiled_policy = compile_policy(&self.policy.load().doc, &self.catalog.load())?;
         self.policy.store(Arc::new(compiled_policy));
-        self.cache.clear().await;
+        self.cache
+            .invalidate_for_policy(&self.policy.load().doc.revision)
+            .await?;
         Ok(())
     }
 
+    async fn reload_policy_from_disk(&self, path: &Path) -> Result<(), RouterError> {
+        let raw = std::fs::read_to_string(path)?;
+        let doc: PolicyDocument = serde_json::from_str(&raw)
+            .or_else(|_| serde_yaml::from_str(&raw))
+            .map_err(|err| RouterError::Planning(format!("parse policy document: {err}")))?;
+        self.reload_policy(doc).await
+    }
+
+    async fn reload_catalog_from_disk(&self, path: &Path) -> Result<(), RouterError> {
+        let raw = std::fs::read_to_string(path)?;
+        let doc: CatalogDocument = serde_json::from_str(&raw)
+            .or_else(|_| serde_yaml::from_str(&raw))
+            .map_err(|err| RouterError::Planning(format!("parse catalog document: {err}")))?;
+        self.reload_catalog(doc).await
+    }
+
     pub async fn reload_overlays(&self) -> Result<(), RouterError> {
-        let overlays = load_overlays(Path::new(&self.overlay_dir))?;
+        let overlays = load_overlays(
+            Path::new(&self.overlay_dir),
+            self.overlay_encryption_key.as_ref(),
+        )?;
         self.overlays.store(Arc::new(overlays));
         Ok(())
     }
 
     pub async fn plan(&self, req: RouteRequest) -> Result<PlanOutcome, RouterError> {
-        let policy = self.policy.load();
-        let catalog = self.catalog.load();
+        let policy = self.policy.load_full();
+        let catalog = self.catalog.load_full();
         let overlays = self.overlays.load();
 
         let alias = policy
@@ -288,9 +453,11 @@ impl RouterEngine {
             }
         }
 
+        let alias_idx = hash_alias(&req.alias);
         let cache_key = CacheKey::derive(
             &policy.doc.revision,
-            hash_alias(&req.alias),
+            &catalog.revision,
+            alias_idx,
             caps_mask.bits() as u64,
             json_mode,
             bucket_tokens(in_tokens),
@@ -305,137 +472,580 @@ impl RouterEngine {
             canonical_match.as_ref().map(canonical_hash).unwrap_or(0),
         );
 
-        if let Some(hit) = self.cache.get(&cache_key).await {
-            let mut response_plan = (*hit.plan).clone();
-            self.attach_stickiness(
-                &policy.doc,
-                &req,
-                &mut response_plan,
-                sticky_claims.as_ref(),
-            )?;
-            let mut effective_reason = hit.route_reason.clone();
-            if sticky_claims.is_some() {
-                effective_reason = Some("policy_lock".into());
-            }
+        let stickiness_cfg = policy.doc.defaults.stickiness.clone();
+        let valid_until = if stickiness_cfg.max_turns > 0 && stickiness_cfg.window_ms > 0 {
+            Some(Utc::now() + ChronoDuration::milliseconds(stickiness_cfg.window_ms as i64))
+        } else {
+            None
+        };
+
+        let alias_for_compute = alias.clone();
+        let policy_for_compute = Arc::clone(&policy);
+        let catalog_for_compute = Arc::clone(&catalog);
+        let health_for_compute = self.health.clone();
+        let req_for_compute = req.clone();
+        let canonical_for_compute = canonical_match.clone();
+        let forced_tag_for_compute = forced_tag.clone();
+        let prompt_overlays_for_compute = prompt_overlays;
+        let freeze_key_for_compute = freeze_key;
+        let content_used_for_compute = content_used;
+        let cache_ttl_ms = self.cache_ttl_ms as u32;
+        let sticky_claims_for_compute = sticky_claims.clone();
+        let mut base_reason_for_compute = base_reason.clone();
+
+        let (hit, computed) = self
+            .cache
+            .get_or_compute(
+                cache_key,
+                valid_until,
+                policy.doc.revision.clone(),
+                alias_idx,
+                async move {
+                let forced_tag_value = forced_tag_for_compute;
+                let scored = score_candidates(ScoreContext {
+                    req: &req_for_compute,
+                    alias: &alias_for_compute,
+                    policy: &policy_for_compute.doc,
+                    catalog: &catalog_for_compute,
+                    health: &health_for_compute,
+                    caps_mask,
+                    in_tokens,
+                    out_tokens,
+                    overlay_size_bytes: prompt_overlays_for_compute.overlay_size_bytes.unwrap_or(0),
+                    region_mask,
+                    boost,
+                    forced_tier: forced_tag_value.as_deref(),
+                    canonical_hint: canonical_for_compute.as_ref(),
+                    debug_trace: wants_scoring_trace(&req_for_compute),
+                })?;
+                let mut candidates = scored.candidates;
+                let mut any_ejected = scored.any_ejected;
+                let mut scoring_trace = scored.trace;
+                if candidates.is_empty() && forced_tag_value.is_some() {
+                    base_reason_for_compute = None;
+                    let scored = score_candidates(ScoreContext {
+                        req: &req_for_compute,
+                        alias: &alias_for_compute,
+                        policy: &policy_for_compute.doc,
+                        catalog: &catalog_for_compute,
+                        health: &health_for_compute,
+                        caps_mask,
+                        in_tokens,
+                        out_tokens,
+                        overlay_size_bytes: prompt_overlays_for_compute.overlay_size_bytes.unwrap_or(0),
+                        region_mask,
+                        boost,
+                        forced_tier: None,
+                        canonical_hint: canonical_for_compute.as_ref(),
+                        debug_trace: wants_scoring_trace(&req_for_compute),
+                    })?;
+                    candidates = scored.candidates;
+                    any_ejected = any_ejected || scored.any_ejected;
+                    scoring_trace = scored.trace;
+                }
+
+                let best =
+                    choose_primary(&candidates, sticky_claims_for_compute.as_ref(), &req_for_compute.alias)
+                        .ok_or_else(|| RouterError::Planning("no candidates after scoring".into()))?;
+
+                if base_reason_for_compute.is_none() && any_ejected {
+                    base_reason_for_compute = Some("health_ejected".into());
+                }
+
+                if sticky_claims_for_compute
+                    .as_ref()
+                    .map(|claims| claims.alias == req_for_compute.alias && claims.model_id == best.model.id)
+                    .unwrap_or(false)
+                {
+                    base_reason_for_compute = Some("policy_lock".into());
+                }
+
+                let fallbacks = build_fallbacks(&candidates, best)
+                    .into_iter()
+                    .map(|cand| Fallback {
+                        base_url: cand.model.base_url.clone(),
+                        mode: cand.model.mode.clone(),
+                        model_id: cand.model.id.clone(),
+                        reason: Some("alternate".into()),
+                        penalty: Some(cand.penalty),
+                    })
+                    .collect::<Vec<_>>();
+
+                let plan_blueprint = materialize_plan(PlanAssembly {
+                    req: &req_for_compute,
+                    policy: &policy_for_compute.doc,
+                    prompt_overlays: prompt_overlays_for_compute,
+                    primary: best,
+                    fallbacks: &fallbacks,
+                    out_tokens,
+                    content_used: content_used_for_compute,
+                    cache_ttl_ms,
+                    freeze_key: freeze_key_for_compute,
+                    catalog_revision: &catalog_for_compute.revision,
+                    canonical: canonical_for_compute,
+                    scoring_trace,
+                })?;
+
+                Ok((Arc::new(plan_blueprint), base_reason_for_compute))
+            },
+            )
+            .await?;
+
+        let mut response_plan = (*hit.plan).clone();
+        self.attach_stickiness(
+            &policy.doc,
+            &req,
+            &mut response_plan,
+            sticky_claims.as_ref(),
+        )?;
+        let mut effective_reason = hit.route_reason.clone();
+        if sticky_claims.is_some() {
+            effective_reason = Some("policy_lock".into());
+        }
+
+        self.metrics
+            .total_requests
+            .entry(req.alias.clone())
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+        if computed {
             self.metrics
-                .total_requests
-                .entry(req.alias.clone())
+                .model_share
+                .entry(response_plan.upstream.model_id.clone())
                 .and_modify(|count| *count += 1)
                 .or_insert(1);
             self.metrics
-                .cache_hits
+                .cache_misses
+                .entry(req.alias.clone())
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        } else {
+            let bucket = match hit.status {
+                CacheStatus::Stale => &self.metrics.cache_stale,
+                _ => &self.metrics.cache_hits,
+            };
+            bucket
                 .entry(req.alias.clone())
                 .and_modify(|count| *count += 1)
                 .or_insert(1);
-            return Ok(PlanOutcome {
-                plan: response_plan,
-                cache_status: hit.status,
-                policy_revision: policy.doc.revision.clone(),
-                catalog_revision: catalog.revision.clone(),
-                route_reason: effective_reason,
+        }
+        self.metrics
+            .route_reasons
+            .entry(effective_reason.clone().unwrap_or_else(|| "none".into()))
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+        let observed_latency_ms =
+            self.health.snapshot(&response_plan.upstream.model_id).p50_ms as f64;
+        self.metrics
+            .latency_histograms
+            .entry(response_plan.upstream.model_id.clone())
+            .or_default()
+            .observe(observed_latency_ms);
+
+        Ok(PlanOutcome {
+            plan: response_plan,
+            cache_status: hit.status,
+            policy_revision: policy.doc.revision.clone(),
+            catalog_revision: catalog.revision.clone(),
+            route_reason: effective_reason,
+        })
+    }
+
+    pub async fn plan_batch(&self, reqs: Vec<RouteRequest>) -> Vec<Result<PlanOutcome, RouterError>> {
+        let policy = self.policy.load_full();
+        let catalog = self.catalog.load_full();
+        let overlays = self.overlays.load();
+
+        let mut preambles = Vec::with_capacity(reqs.len());
+        for req in reqs {
+            preambles.push(self.batch_preamble(&policy, &catalog, &overlays, req).await);
+        }
+
+        let mut groups: HashMap<CacheKey, usize> = HashMap::new();
+        let mut group_reps: Vec<usize> = Vec::new();
+        let mut group_of: Vec<Option<usize>> = vec![None; preambles.len()];
+        for (idx, preamble) in preambles.iter().enumerate() {
+            if let Ok(p) = preamble {
+                let group_idx = *groups.entry(p.cache_key).or_insert_with(|| {
+                    group_reps.push(idx);
+                    group_reps.len() - 1
+                });
+                group_of[idx] = Some(group_idx);
+            }
+        }
+
+        let computations = group_reps.iter().enumerate().map(|(slot, &rep_idx)| {
+            let preamble = preambles[rep_idx]
+                .as_ref()
+                .expect("group representative always has a successful preamble");
+            let computation = self.score_for_key(&policy, &catalog, preamble);
+            async move { (slot, computation.await) }
+        });
+        let mut group_results: Vec<Option<Result<(CacheHit, bool), String>>> =
+            (0..group_reps.len()).map(|_| None).collect();
+        let mut in_flight = futures_util::stream::iter(computations)
+            .buffer_unordered(self.batch_concurrency_limit);
+        while let Some((slot, result)) = in_flight.next().await {
+            group_results[slot] = Some(result);
+        }
+        let group_results: Vec<Result<(CacheHit, bool), String>> = group_results
+            .into_iter()
+            .map(|result| result.expect("every group slot resolved"))
+            .collect();
+
+        preambles
+            .into_iter()
+            .enumerate()
+            .map(|(idx, preamble)| {
+                let preamble = preamble?;
+                let group_idx = group_of[idx].expect("successful preamble was grouped");
+                match &group_results[group_idx] {
+                    Ok((hit, computed)) => {
+                        self.finish_plan_outcome(&policy, &catalog, preamble, hit.clone(), *computed)
+                    }
+                    Err(message) => Err(RouterError::Planning(message.clone())),
+                }
+            })
+            .collect()
+    }
+
+    async fn batch_preamble(
+        &self,
+        policy: &Arc<CompiledPolicy>,
+        catalog: &Arc<CompiledCatalog>,
+        overlays: &Arc<OverlayStore>,
+        req: RouteRequest,
+    ) -> Result<BatchPreamble, RouterError> {
+        let alias = policy
+            .alias_map
+            .get(&req.alias)
+            .ok_or_else(|| RouterError::UnknownAlias(req.alias.clone()))?
+            .clone();
+
+        let caps_mask = caps_from_request(&req);
+        let json_mode = req
+            .params
+            .as_ref()
+            .and_then(|val| val.get("json_mode"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let in_tokens = req
+            .estimates
+            .as_ref()
+            .and_then(|est| est.prompt_tokens)
+            .unwrap_or(DEFAULT_PROMPT_TOKENS);
+        let mut out_tokens = req
+            .estimates
+            .as_ref()
+            .and_then(|est| est.max_output_tokens)
+            .unwrap_or_else(|| policy.doc.defaults.max_output_tokens);
+        if out_tokens == 0 {
+            out_tokens = DEFAULT_OUTPUT_TOKENS;
+        }
+
+        let region_mask = region_from_request(&req);
+        let boost = has_teacher_boost(&req);
+        let sticky_claims = req
+            .overrides
+            .as_ref()
+            .and_then(|ov| ov.get("plan_token"))
+            .and_then(Value::as_str)
+            .and_then(|token| match self.stickiness.verify(token) {
+                Ok(claims) => Some(claims),
+                Err(err) => {
+                    tracing::warn!("invalid stickiness token: {err}");
+                    None
+                }
             });
+        let plan_token_model = sticky_claims
+            .as_ref()
+            .and_then(|claims| catalog.index.get(&claims.model_id))
+            .copied()
+            .unwrap_or_default() as u32;
+        let content_used = determine_content_usage(&req);
+        let freeze_key = freeze_key_from_request(&req, &policy.doc.revision);
+        let prompt_overlays = resolve_overlay(
+            &req,
+            &policy.doc,
+            overlays,
+            policy.doc.defaults.max_overlay_bytes,
+        )?;
+        let overlay_hash = hash_string(
+            prompt_overlays
+                .overlay_fingerprint
+                .as_deref()
+                .unwrap_or("overlay:none"),
+        );
+        let (forced_tier, mut base_reason) = determine_escalation(
+            &req,
+            &policy.doc,
+            policy.escalation_regex.as_ref(),
+            in_tokens,
+            boost,
+        );
+        let forced_tag = forced_tier.as_ref().map(|tier| format!("tier:{}", tier));
+
+        let canonical = if let Some(runtime) = self.embedding.as_ref() {
+            match runtime.select(&req).await {
+                Ok(result) => result,
+                Err(err) => {
+                    tracing::warn!("embedding routing failed: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(selection) = canonical.as_ref() {
+            if base_reason.is_none() {
+                base_reason = Some(format!("canonical:{}", selection.model_id));
+            }
         }
 
-        let forced_tag_value = forced_tag.clone();
-        let mut candidates = score_candidates(ScoreContext {
-            req: &req,
+        let alias_idx = hash_alias(&req.alias);
+        let cache_key = CacheKey::derive(
+            &policy.doc.revision,
+            &catalog.revision,
+            alias_idx,
+            caps_mask.bits() as u64,
+            json_mode,
+            bucket_tokens(in_tokens),
+            bucket_tokens(out_tokens),
+            region_mask.bits(),
+            boost,
+            plan_token_model,
+            overlay_hash,
+            req.privacy_mode,
+            req.api,
+            hash_string(&freeze_key),
+            canonical.as_ref().map(canonical_hash).unwrap_or(0),
+        );
+
+        let stickiness_cfg = policy.doc.defaults.stickiness.clone();
+        let valid_until = if stickiness_cfg.max_turns > 0 && stickiness_cfg.window_ms > 0 {
+            Some(Utc::now() + ChronoDuration::milliseconds(stickiness_cfg.window_ms as i64))
+        } else {
+            None
+        };
+
+        Ok(BatchPreamble {
+            req,
+            sticky_claims,
+            cache_key,
+            valid_until,
+            alias_idx,
             alias,
-            policy: &policy.doc,
-            catalog: &catalog,
-            health: &self.health,
+            canonical,
+            forced_tag,
+            prompt_overlays,
+            freeze_key,
+            content_used,
+            base_reason,
             caps_mask,
             in_tokens,
             out_tokens,
             region_mask,
             boost,
-            forced_tier: forced_tag_value.as_deref(),
-            canonical_hint: canonical_match.as_ref(),
-        })?;
-        if candidates.is_empty() && forced_tag_value.is_some() {
-            base_reason = None;
-            candidates = score_candidates(ScoreContext {
-                req: &req,
-                alias,
-                policy: &policy.doc,
-                catalog: &catalog,
-                health: &self.health,
-                caps_mask,
-                in_tokens,
-                out_tokens,
-                region_mask,
-                boost,
-                forced_tier: None,
-                canonical_hint: canonical_match.as_ref(),
-            })?;
-        }
-
-        let best = choose_primary(&candidates, sticky_claims.as_ref(), &req.alias)
-            .ok_or_else(|| RouterError::Planning("no candidates after scoring".into()))?;
-
-        if sticky_claims
-            .as_ref()
-            .map(|claims| claims.alias == req.alias && claims.model_id == best.model.id)
-            .unwrap_or(false)
-        {
-            base_reason = Some("policy_lock".into());
-        }
+        })
+    }
 
-        let fallbacks = build_fallbacks(&candidates, best)
-            .into_iter()
-            .map(|cand| Fallback {
-                base_url: cand.model.base_url.clone(),
-                mode: cand.model.mode.clone(),
-                model_id: cand.model.id.clone(),
-                reason: Some("alternate".into()),
-                penalty: Some(cand.penalty),
-            })
-            .collect::<Vec<_>>();
+    async fn score_for_key(
+        &self,
+        policy: &Arc<CompiledPolicy>,
+        catalog: &Arc<CompiledCatalog>,
+        preamble: &BatchPreamble,
+    ) -> Result<(CacheHit, bool), String> {
+        let alias_for_compute = preamble.alias.clone();
+        let policy_for_compute = Arc::clone(policy);
+        let catalog_for_compute = Arc::clone(catalog);
+        let health_for_compute = self.health.clone();
+        let req_for_compute = preamble.req.clone();
+        let canonical_for_compute = preamble.canonical.clone();
+        let forced_tag_for_compute = preamble.forced_tag.clone();
+        let prompt_overlays_for_compute = preamble.prompt_overlays.clone();
+        let freeze_key_for_compute = preamble.freeze_key.clone();
+        let content_used_for_compute = preamble.content_used;
+        let cache_ttl_ms = self.cache_ttl_ms as u32;
+        let sticky_claims_for_compute = preamble.sticky_claims.clone();
+        let mut base_reason_for_compute = preamble.base_reason.clone();
+        let caps_mask = preamble.caps_mask;
+        let in_tokens = preamble.in_tokens;
+        let out_tokens = preamble.out_tokens;
+        let region_mask = preamble.region_mask;
+        let boost = preamble.boost;
 
-        let catalog_revision = catalog.revision.clone();
-        let plan_blueprint = materialize_plan(PlanAssembly {
-            req: &req,
-            policy: &policy.doc,
-            prompt_overlays,
-            primary: best,
-            fallbacks: &fallbacks,
-            out_tokens,
-            content_used,
-            cache_ttl_ms: self.cache_ttl_ms as u32,
-            freeze_key,
-            catalog_revision: &catalog_revision,
-            canonical: canonical_match.clone(),
-        })?;
-        let mut response_plan = plan_blueprint.clone();
-        let issued_stickiness = self.attach_stickiness(
+        self.cache
+            .get_or_compute(
+                preamble.cache_key,
+                preamble.valid_until,
+                policy.doc.revision.clone(),
+                preamble.alias_idx,
+                async move {
+                    let forced_tag_value = forced_tag_for_compute;
+                    let scored = score_candidates(ScoreContext {
+                        req: &req_for_compute,
+                        alias: &alias_for_compute,
+                        policy: &policy_for_compute.doc,
+                        catalog: &catalog_for_compute,
+                        health: &health_for_compute,
+                        caps_mask,
+                        in_tokens,
+                        out_tokens,
+                        overlay_size_bytes: prompt_overlays_for_compute.overlay_size_bytes.unwrap_or(0),
+                        region_mask,
+                        boost,
+                        forced_tier: forced_tag_value.as_deref(),
+                        canonical_hint: canonical_for_compute.as_ref(),
+                        debug_trace: wants_scoring_trace(&req_for_compute),
+                    })?;
+                    let mut candidates = scored.candidates;
+                    let mut any_ejected = scored.any_ejected;
+                    let mut scoring_trace = scored.trace;
+                    if candidates.is_empty() && forced_tag_value.is_some() {
+                        base_reason_for_compute = None;
+                        let scored = score_candidates(ScoreContext {
+                            req: &req_for_compute,
+                            alias: &alias_for_compute,
+                            policy: &policy_for_compute.doc,
+                            catalog: &catalog_for_compute,
+                            health: &health_for_compute,
+                            caps_mask,
+                            in_tokens,
+                            out_tokens,
+                            overlay_size_bytes: prompt_overlays_for_compute
+                                .overlay_size_bytes
+                                .unwrap_or(0),
+                            region_mask,
+                            boost,
+                            forced_tier: None,
+                            canonical_hint: canonical_for_compute.as_ref(),
+                            debug_trace: wants_scoring_trace(&req_for_compute),
+                        })?;
+                        candidates = scored.candidates;
+                        any_ejected = any_ejected || scored.any_ejected;
+                        scoring_trace = scored.trace;
+                    }
+
+                    let best = choose_primary(
+                        &candidates,
+                        sticky_claims_for_compute.as_ref(),
+                        &req_for_compute.alias,
+                    )
+                    .ok_or_else(|| RouterError::Planning("no candidates after scoring".into()))?;
+
+                    if base_reason_for_compute.is_none() && any_ejected {
+                        base_reason_for_compute = Some("health_ejected".into());
+                    }
+
+                    if sticky_claims_for_compute
+                        .as_ref()
+                        .map(|claims| {
+                            claims.alias == req_for_compute.alias && claims.model_id == best.model.id
+                        })
+                        .unwrap_or(false)
+                    {
+                        base_reason_for_compute = Some("policy_lock".into());
+                    }
+
+                    let fallbacks = build_fallbacks(&candidates, best)
+                        .into_iter()
+                        .map(|cand| Fallback {
+                            base_url: cand.model.base_url.clone(),
+                            mode: cand.model.mode.clone(),
+                            model_id: cand.model.id.clone(),
+                            reason: Some("alternate".into()),
+                            penalty: Some(cand.penalty),
+                        })
+                        .collect::<Vec<_>>();
+
+                    let plan_blueprint = materialize_plan(PlanAssembly {
+                        req: &req_for_compute,
+                        policy: &policy_for_compute.doc,
+                        prompt_overlays: prompt_overlays_for_compute,
+                        primary: best,
+                        fallbacks: &fallbacks,
+                        out_tokens,
+                        content_used: content_used_for_compute,
+                        cache_ttl_ms,
+                        freeze_key: freeze_key_for_compute,
+                        catalog_revision: &catalog_for_compute.revision,
+                        canonical: canonical_for_compute,
+                        scoring_trace,
+                    })?;
+
+                    Ok((Arc::new(plan_blueprint), base_reason_for_compute))
+                },
+            )
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    fn finish_plan_outcome(
+        &self,
+        policy: &Arc<CompiledPolicy>,
+        catalog: &Arc<CompiledCatalog>,
+        preamble: BatchPreamble,
+        hit: CacheHit,
+        computed: bool,
+    ) -> Result<PlanOutcome, RouterError> {
+        let req = preamble.req;
+        let mut response_plan = (*hit.plan).clone();
+        self.attach_stickiness(
             &policy.doc,
             &req,
             &mut response_plan,
-            sticky_claims.as_ref(),
+            preamble.sticky_claims.as_ref(),
         )?;
-        let valid_until = issued_stickiness.map(|claims| claims.expires_at);
-
-        let plan_arc = Arc::new(plan_blueprint);
-        self.cache
-            .insert(cache_key, plan_arc, valid_until, base_reason.clone())
-            .await;
+        let mut effective_reason = hit.route_reason.clone();
+        if preamble.sticky_claims.is_some() {
+            effective_reason = Some("policy_lock".into());
+        }
 
         self.metrics
-            .model_share
-            .entry(best.model.id.clone())
+            .total_requests
+            .entry(req.alias.clone())
             .and_modify(|count| *count += 1)
             .or_insert(1);
+        if computed {
+            self.metrics
+                .model_share
+                .entry(response_plan.upstream.model_id.clone())
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+            self.metrics
+                .cache_misses
+                .entry(req.alias.clone())
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        } else {
+            let bucket = match hit.status {
+                CacheStatus::Stale => &self.metrics.cache_stale,
+                _ => &self.metrics.cache_hits,
+            };
+            bucket
+                .entry(req.alias.clone())
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        }
         self.metrics
-            .total_requests
-            .entry(req.alias.clone())
+            .route_reasons
+            .entry(effective_reason.clone().unwrap_or_else(|| "none".into()))
             .and_modify(|count| *count += 1)
             .or_insert(1);
+        let observed_latency_ms =
+            self.health.snapshot(&response_plan.upstream.model_id).p50_ms as f64;
+        self.metrics
+            .latency_histograms
+            .entry(response_plan.upstream.model_id.clone())
+            .or_default()
+            .observe(observed_latency_ms);
 
         Ok(PlanOutcome {
             plan: response_plan,
-            cache_status: CacheStatus::Miss,
+            cache_status: hit.status,
             policy_revision: policy.doc.revision.clone(),
-            catalog_revision,
-            route_reason: base_reason,
+            catalog_revision: catalog.revision.clone(),
+            route_reason: effective_reason,
         })
     }
 
@@ -460,6 +1070,77 @@ impl RouterEngine {
         }
     }
 
+    pub fn render_metrics(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# TYPE router_requests_total counter\n");
+        for entry in self.metrics.total_requests.iter() {
+            out.push_str(&format!(
+                "router_requests_total{{alias=\"{}\"}} {}\n",
+                escape_label(entry.key()),
+                entry.value()
+            ));
+        }
+
+        out.push_str("# TYPE router_model_share_total counter\n");
+        for entry in self.metrics.model_share.iter() {
+            out.push_str(&format!(
+                "router_model_share_total{{model=\"{}\"}} {}\n",
+                escape_label(entry.key()),
+                entry.value()
+            ));
+        }
+
+        out.push_str("# TYPE router_cache_total counter\n");
+        for (status, map) in [
+            ("hit", &self.metrics.cache_hits),
+            ("miss", &self.metrics.cache_misses),
+            ("stale", &self.metrics.cache_stale),
+        ] {
+            for entry in map.iter() {
+                out.push_str(&format!(
+                    "router_cache_total{{alias=\"{}\",status=\"{status}\"}} {}\n",
+                    escape_label(entry.key()),
+                    entry.value()
+                ));
+            }
+        }
+
+        out.push_str("# TYPE router_route_reason_total counter\n");
+        for entry in self.metrics.route_reasons.iter() {
+            out.push_str(&format!(
+                "router_route_reason_total{{reason=\"{}\"}} {}\n",
+                escape_label(entry.key()),
+                entry.value()
+            ));
+        }
+
+        out.push_str("# TYPE router_upstream_latency_ms histogram\n");
+        for entry in self.metrics.latency_histograms.iter() {
+            let model = escape_label(entry.key());
+            let hist = entry.value();
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(hist.buckets.iter()) {
+                out.push_str(&format!(
+                    "router_upstream_latency_ms_bucket{{model=\"{model}\",le=\"{bound}\"}} {count}\n"
+                ));
+            }
+            out.push_str(&format!(
+                "router_upstream_latency_ms_bucket{{model=\"{model}\",le=\"+Inf\"}} {}\n",
+                hist.count
+            ));
+            out.push_str(&format!(
+                "router_upstream_latency_ms_sum{{model=\"{model}\"}} {}\n",
+                hist.sum_ms
+            ));
+            out.push_str(&format!(
+                "router_upstream_latency_ms_count{{model=\"{model}\"}} {}\n",
+                hist.count
+            ));
+        }
+
+        out
+    }
+
     pub fn policy_document(&self) -> PolicyDocument {
         self.policy.load().doc.clone()
     }
@@ -468,6 +1149,169 @@ impl RouterEngine {
         self.catalog.load().raw.clone()
     }
 
+    pub fn catalog_snapshot_with_health(&self) -> Value {
+        let doc = self.catalog.load().raw.clone();
+        let mut value = serde_json::to_value(&doc).unwrap_or(Value::Null);
+        if let Some(models) = value.get_mut("models").and_then(Value::as_array_mut) {
+            for model in models.iter_mut() {
+                let id = model.get("id").and_then(Value::as_str).map(String::from);
+                let Some(id) = id else { continue };
+                let stats = self.health.snapshot(&id);
+                if let Some(probe) = stats.last_probe.as_ref() {
+                    model["probe"] = serde_json::json!({
+                        "success": probe.success,
+                        "latency_ms": probe.latency_ms,
+                        "checked_at": probe.checked_at.to_rfc3339(),
+                    });
+                }
+            }
+        }
+        value
+    }
+
+    pub fn spawn_health_prober(self: Arc<Self>, cfg: HealthProbeConfig) {
+        if !cfg.enabled {
+            return;
+        }
+        tokio::spawn(async move {
+            let client = reqwest::Client::builder()
+                .timeout(StdDuration::from_millis(cfg.timeout_ms))
+                .build()
+                .unwrap_or_default();
+            let mut ticker = tokio::time::interval(StdDuration::from_millis(cfg.interval_ms));
+            loop {
+                ticker.tick().await;
+                let catalog = self.catalog.load();
+                for model in &catalog.models {
+                    let url = format!(
+                        "{}{}",
+                        model.base_url.trim_end_matches('/'),
+                        cfg.path
+                    );
+                    let client = client.clone();
+                    let health = self.health.clone();
+                    let model_id = model.id.clone();
+                    tokio::spawn(async move {
+                        let started = Instant::now();
+                        let result = client.get(&url).send().await;
+                        let latency_ms = started.elapsed().as_millis() as u32;
+                        let success = matches!(
+                            &result,
+                            Ok(resp) if resp.status().is_success() || resp.status().is_client_error()
+                        );
+                        health.record_probe(&model_id, success, latency_ms);
+                    });
+                }
+            }
+        });
+    }
+
+    pub fn spawn_cache_persistence(self: Arc<Self>, cfg: CachePersistConfig) {
+        if !cfg.enabled {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(StdDuration::from_millis(cfg.interval_ms));
+            loop {
+                ticker.tick().await;
+                match self.cache.flush_to_disk().await {
+                    Ok(written) => tracing::debug!(written, "plan cache flushed to disk"),
+                    Err(err) => tracing::warn!("plan cache flush failed: {err}"),
+                }
+            }
+        });
+    }
+
+    pub fn spawn_gossip(self: Arc<Self>, cfg: GossipConfig) {
+        if !cfg.enabled || cfg.peers.is_empty() {
+            return;
+        }
+        tokio::spawn(async move {
+            match GossipHandle::bind(&cfg).await {
+                Ok(Some(handle)) => {
+                    let handle = Arc::new(handle);
+                    self.cache.attach_gossip(handle.clone());
+                    handle.run(self.cache.clone()).await;
+                }
+                Ok(None) => {}
+                Err(err) => tracing::warn!("gossip bind failed: {err}"),
+            }
+        });
+    }
+
+    pub fn spawn_hot_reload(self: Arc<Self>, policy_path: PathBuf, catalog_path: PathBuf, cfg: HotReloadConfig) {
+        if !cfg.enabled {
+            return;
+        }
+        let (tx, rx) = std::sync::mpsc::channel::<notify::Event>();
+        let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        }) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                tracing::warn!("failed to start config file watcher: {err}");
+                return;
+            }
+        };
+        for path in [&policy_path, &catalog_path] {
+            let watch_target = path.parent().unwrap_or(path);
+            if let Err(err) = watcher.watch(watch_target, notify::RecursiveMode::NonRecursive) {
+                tracing::warn!("failed to watch {:?}: {err}", watch_target);
+            }
+        }
+        let handle = tokio::runtime::Handle::current();
+        let debounce = StdDuration::from_millis(cfg.debounce_ms.max(1));
+        tokio::task::spawn_blocking(move || {
+            let _watcher = watcher; // keep alive for the life of this thread
+            let mut policy_pending = false;
+            let mut catalog_pending = false;
+            loop {
+                match rx.recv_timeout(debounce) {
+                    Ok(event) => {
+                        if !matches!(
+                            event.kind,
+                            notify::EventKind::Modify(_) | notify::EventKind::Create(_)
+                        ) {
+                            continue;
+                        }
+                        for changed in &event.paths {
+                            if changed.file_name() == policy_path.file_name() {
+                                policy_pending = true;
+                            } else if changed.file_name() == catalog_path.file_name() {
+                                catalog_pending = true;
+                            }
+                        }
+                    }
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                        if policy_pending {
+                            policy_pending = false;
+                            let engine = self.clone();
+                            let path = policy_path.clone();
+                            handle.block_on(async move {
+                                if let Err(err) = engine.reload_policy_from_disk(&path).await {
+                                    tracing::warn!("policy hot-reload failed: {err}");
+                                }
+                            });
+                        }
+                        if catalog_pending {
+                            catalog_pending = false;
+                            let engine = self.clone();
+                            let path = catalog_path.clone();
+                            handle.block_on(async move {
+                                if let Err(err) = engine.reload_catalog_from_disk(&path).await {
+                                    tracing::warn!("catalog hot-reload failed: {err}");
+                                }
+                            });
+                        }
+                    }
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+    }
+
     pub fn policy_revision(&self) -> String {
         self.policy.load().doc.revision.clone()
     }
@@ -483,6 +1327,17 @@ struct CandidateRef<'a> {
     est_cost_micro: u64,
     est_latency_ms: u32,
     penalty: f32,
+    breaker_state: EjectionState,
+    fit_cost: f32,
+    fit_latency: f32,
+    fit_health: f32,
+    fit_context: f32,
+}
+
+struct ScoredCandidates<'a> {
+    candidates: Vec<CandidateRef<'a>>,
+    any_ejected: bool,
+    trace: Option<ScoringTrace>,
 }
 
 struct ScoreContext<'req, 'a, 'tier> {
@@ -494,10 +1349,20 @@ struct ScoreContext<'req, 'a, 'tier> {
     caps_mask: CapabilityFlags,
     in_tokens: u32,
     out_tokens: u32,
+    overlay_size_bytes: u32,
     region_mask: RegionMask,
     boost: bool,
     forced_tier: Option<&'tier str>,
     canonical_hint: Option<&'tier CanonicalSelection>,
+    debug_trace: bool,
+}
+
+fn wants_scoring_trace(req: &RouteRequest) -> bool {
+    req.overrides
+        .as_ref()
+        .and_then(|overrides| overrides.get("debug_trace"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
 }
 
 #[derive(Clone, Copy)]
@@ -520,6 +1385,79 @@ struct PlanAssembly<'req, 'a> {
     freeze_key: String,
     catalog_revision: &'req str,
     canonical: Option<CanonicalSelection>,
+    scoring_trace: Option<ScoringTrace>,
+}
+
+fn base_revision(revision: &str) -> &str {
+    revision.split("@sha256:").next().unwrap_or(revision)
+}
+
+fn canonical_digest<T: Serialize>(doc: &T) -> Result<String, RouterError> {
+    let canonical = serde_json::to_value(doc)
+        .and_then(|value| serde_json::to_vec(&value))
+        .map_err(|err| RouterError::IntegrityViolation(format!("canonicalize document: {err}")))?;
+    let mut hasher = Sha256::new();
+    hasher.update(&canonical);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn verify_integrity(
+    label: &str,
+    digest: &str,
+    integrity: Option<&IntegrityEnvelope>,
+    signing_key: Option<&VerifyingKey>,
+) -> Result<(), RouterError> {
+    let integrity = match integrity {
+        Some(envelope) => envelope,
+        None => {
+            return if signing_key.is_some() {
+                Err(RouterError::IntegrityViolation(format!(
+                    "{label} reload requires a signed integrity envelope but none was supplied"
+                )))
+            } else {
+                Ok(())
+            };
+        }
+    };
+
+    if let Some(expected) = integrity.expected_sha256.as_deref() {
+        if !expected.eq_ignore_ascii_case(digest) {
+            return Err(RouterError::IntegrityViolation(format!(
+                "{label} digest mismatch: expected {expected}, computed {digest}"
+            )));
+        }
+    }
+
+    if let Some(key) = signing_key {
+        let signature_hex = integrity.signature.as_deref().ok_or_else(|| {
+            RouterError::IntegrityViolation(format!(
+                "{label} reload requires a detached signature but none was supplied"
+            ))
+        })?;
+        let signature_bytes = hex::decode(signature_hex).map_err(|err| {
+            RouterError::IntegrityViolation(format!("{label} signature is not valid hex: {err}"))
+        })?;
+        let signature = Signature::from_slice(&signature_bytes).map_err(|err| {
+            RouterError::IntegrityViolation(format!("{label} signature malformed: {err}"))
+        })?;
+        key.verify(digest.as_bytes(), &signature).map_err(|_| {
+            RouterError::IntegrityViolation(format!("{label} signature verification failed"))
+        })?;
+    }
+
+    Ok(())
+}
+
+fn parse_signing_public_key(hex_key: &str) -> Result<VerifyingKey, RouterError> {
+    let bytes = hex::decode(hex_key).map_err(|err| {
+        RouterError::IntegrityViolation(format!("reload signing public key is not valid hex: {err}"))
+    })?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+        RouterError::IntegrityViolation("reload signing public key must be 32 bytes".into())
+    })?;
+    VerifyingKey::from_bytes(&bytes).map_err(|err| {
+        RouterError::IntegrityViolation(format!("reload signing public key is invalid: {err}"))
+    })
 }
 
 fn compile_catalog(doc: &CatalogDocument) -> Result<CompiledCatalog, RouterError> {
@@ -619,6 +1557,10 @@ fn compile_catalog(doc: &CatalogDocument) -> Result<CompiledCatalog, RouterError
             capabilities: capability,
             regions,
             context_tokens: model.capabilities.context_tokens.max(1024),
+            cache_window_tokens: model
+                .capabilities
+                .prompt_cache_window_tokens
+                .unwrap_or(model.capabilities.context_tokens.max(1024)),
             prices,
             target_latency_ms,
             base_latency_ms,
@@ -684,7 +1626,37 @@ fn compile_policy(
     })
 }
 
-fn load_overlays(dir: &Path) -> Result<OverlayStore, RouterError> {
+const OVERLAY_ENVELOPE_MAGIC: &[u8] = b"EDUOVL1";
+const OVERLAY_NONCE_LEN: usize = 24;
+
+fn parse_overlay_encryption_key(hex_key: &str) -> Result<[u8; 32], RouterError> {
+    let bytes = hex::decode(hex_key).map_err(|err| {
+        RouterError::OverlayDecryption(format!("overlay encryption key is not valid hex: {err}"))
+    })?;
+    bytes
+        .try_into()
+        .map_err(|_| RouterError::OverlayDecryption("overlay encryption key must be 32 bytes".into()))
+}
+
+fn decrypt_overlay(envelope: &[u8], key: &[u8; 32]) -> Result<String, RouterError> {
+    if envelope.len() < OVERLAY_NONCE_LEN {
+        return Err(RouterError::OverlayDecryption(
+            "encrypted overlay is truncated before its nonce".into(),
+        ));
+    }
+    let (nonce_bytes, sealed) = envelope.split_at(OVERLAY_NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new_from_slice(key)
+        .map_err(|_| RouterError::OverlayDecryption("invalid overlay encryption key".into()))?;
+    let nonce = XNonce::from_slice(nonce_bytes);
+    let plaintext = cipher.decrypt(nonce, sealed).map_err(|_| {
+        RouterError::OverlayDecryption("overlay decryption/authentication failed".into())
+    })?;
+    String::from_utf8(plaintext).map_err(|err| {
+        RouterError::OverlayDecryption(format!("decrypted overlay is not valid utf8: {err}"))
+    })
+}
+
+fn load_overlays(dir: &Path, encryption_key: Option<&[u8; 32]>) -> Result<OverlayStore, RouterError> {
     let mut content = HashMap::new();
     match std::fs::read_dir(dir) {
         Ok(entries) => {
@@ -693,8 +1665,22 @@ fn load_overlays(dir: &Path) -> Result<OverlayStore, RouterError> {
                 let path = entry.path();
                 if path.is_file() {
                     if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                        let data = std::fs::read_to_string(&path)?;
-                        content.insert(name.to_string(), data);
+                        let raw = std::fs::read(&path)?;
+                        let text = if let Some(sealed) = raw.strip_prefix(OVERLAY_ENVELOPE_MAGIC) {
+                            let key = encryption_key.ok_or_else(|| {
+                                RouterError::OverlayDecryption(format!(
+                                    "overlay '{name}' is encrypted but no overlay encryption key is configured"
+                                ))
+                            })?;
+                            decrypt_overlay(sealed, key)?
+                        } else {
+                            String::from_utf8(raw).map_err(|err| {
+                                RouterError::Planning(format!(
+                                    "overlay '{name}' is not valid utf8: {err}"
+                                ))
+                            })?
+                        };
+                        content.insert(name.to_string(), text);
                     }
                 }
             }
@@ -880,10 +1866,45 @@ fn hash_string(value: &str) -> u64 {
     hasher.finish()
 }
 
+fn revision_advances(current: &str, incoming: &str, allow_equal: bool) -> bool {
+    let ordering = match (current.parse::<u64>(), incoming.parse::<u64>()) {
+        (Ok(current), Ok(incoming)) => incoming.cmp(&current),
+        _ => incoming.cmp(current),
+    };
+    match ordering {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Equal => allow_equal,
+        std::cmp::Ordering::Less => false,
+    }
+}
+
 fn score_candidates<'req, 'a, 'tier>(
     ctx: ScoreContext<'req, 'a, 'tier>,
-) -> Result<Vec<CandidateRef<'a>>, RouterError> {
+) -> Result<ScoredCandidates<'a>, RouterError> {
     let mut scored = Vec::new();
+    let mut any_ejected = false;
+    let weights = &ctx.policy.weights;
+    let mut trace: Option<HashMap<String, CandidateTrace>> = if ctx.debug_trace {
+        Some(HashMap::new())
+    } else {
+        None
+    };
+    macro_rules! reject {
+        ($model:expr, $reason:expr) => {
+            if let Some(map) = trace.as_mut() {
+                map.entry($model.id.clone()).or_insert(CandidateTrace {
+                    model_id: $model.id.clone(),
+                    weight_cost: weights.cost,
+                    weight_latency: weights.latency,
+                    weight_health: weights.health,
+                    weight_context: weights.context,
+                    rejected: Some($reason.to_string()),
+                    ..Default::default()
+                });
+            }
+        };
+    }
+
     for idx in &ctx.alias.candidates {
         let model = ctx
             .catalog
@@ -894,19 +1915,35 @@ fn score_candidates<'req, 'a, 'tier>(
             .capabilities
             .contains(ctx.caps_mask | ctx.alias.require_caps)
         {
+            reject!(model, "caps_mismatch");
             continue;
         }
         if !ctx.alias.allowed_regions.intersects(ctx.region_mask) {
+            reject!(model, "region_mismatch");
             continue;
         }
         if !ctx.region_mask.intersects(model.regions) && !model.regions.contains(RegionMask::GLOBAL)
         {
+            reject!(model, "region_mismatch");
             continue;
         }
         if model.context_tokens < ctx.in_tokens + ctx.out_tokens {
+            reject!(model, "context_too_small");
             continue;
         }
         if model.status == ModelStatus::Offline {
+            reject!(model, "offline");
+            continue;
+        }
+        let ejection = ctx.health.check_ejection(
+            &model.id,
+            ctx.policy.ejection.error_rate_threshold,
+            ctx.policy.ejection.min_samples,
+            ctx.policy.ejection.base_cooldown_ms,
+        );
+        if ejection == EjectionState::Ejected {
+            any_ejected = true;
+            reject!(model, "breaker_open");
             continue;
         }
         if let Some(tag) = ctx.forced_tier {
@@ -915,29 +1952,37 @@ fn score_candidates<'req, 'a, 'tier>(
                 .iter()
                 .any(|entry| entry.eq_ignore_ascii_case(tag))
             {
+                reject!(model, "forced_tier_mismatch");
                 continue;
             }
         }
 
+        let conversation_turns = ctx
+            .req
+            .conversation
+            .as_ref()
+            .and_then(|conv| conv.turns)
+            .unwrap_or(0);
         let use_prompt_cache = model.capabilities.contains(CapabilityFlags::PROMPT_CACHE)
-            && (ctx
-                .req
-                .conversation
-                .as_ref()
-                .and_then(|conv| conv.turns)
-                .unwrap_or(0)
-                > 0
+            && (conversation_turns > 0
                 || ctx
                     .req
                     .overrides
                     .as_ref()
                     .and_then(|ov| ov.get("plan_token"))
                     .is_some());
+        let prompt_cache_ctx = use_prompt_cache.then(|| PromptCacheContext {
+            turns: conversation_turns,
+            overlay_size_bytes: ctx.overlay_size_bytes,
+            base_ratio: ctx.policy.defaults.cache_base_ratio,
+            ttl_decay: ctx.policy.defaults.cache_ttl_decay,
+        });
 
         let est_cost_micro =
-            estimate_cost_micro(model, ctx.in_tokens, ctx.out_tokens, use_prompt_cache);
+            estimate_cost_micro(model, ctx.in_tokens, ctx.out_tokens, prompt_cache_ctx.as_ref());
         if let Some(budget) = &ctx.req.budget {
             if est_cost_micro > budget.amount_micro {
+                reject!(model, "over_budget");
                 continue;
             }
         }
@@ -948,12 +1993,13 @@ fn score_candidates<'req, 'a, 'tier>(
         if let Some(targets) = ctx.req.targets.as_ref() {
             if let Some(max_latency) = targets.p95_latency_ms {
                 if est_latency_ms > max_latency {
+                    reject!(model, "over_latency");
                     continue;
                 }
             }
         }
 
-        let mut score = compute_score(
+        let breakdown = compute_score(
             model,
             &health_snapshot,
             ScoreFactors {
@@ -965,44 +2011,213 @@ fn score_candidates<'req, 'a, 'tier>(
             ctx.policy,
             ctx.boost,
         );
+        let mut score = breakdown.score;
+        let mut canonical_hint_delta = 0.0;
         if let Some(hint) = ctx.canonical_hint {
             if hint.model_id == model.id {
-                score += hint.score;
+                canonical_hint_delta = hint.score;
+                score += canonical_hint_delta;
             }
         }
+        if ejection == EjectionState::HalfOpen {
+            any_ejected = true;
+            score -= 1.0;
+        }
+
+        if let Some(map) = trace.as_mut() {
+            map.insert(
+                model.id.clone(),
+                CandidateTrace {
+                    model_id: model.id.clone(),
+                    score,
+                    fit_cost: breakdown.fit_cost,
+                    fit_latency: breakdown.fit_latency,
+                    fit_health: breakdown.fit_health,
+                    fit_context: breakdown.fit_context,
+                    weight_cost: weights.cost,
+                    weight_latency: weights.latency,
+                    weight_health: weights.health,
+                    weight_context: weights.context,
+                    tier_bonus: breakdown.tier_bonus,
+                    degraded_penalty: breakdown.degraded_penalty,
+                    canonical_hint_delta,
+                    rejected: None,
+                },
+            );
+        }
 
         scored.push(CandidateRef {
             model,
             score,
             est_cost_micro,
             est_latency_ms,
-            penalty: if model.status == ModelStatus::Degraded {
+            penalty: if ejection == EjectionState::HalfOpen {
+                0.5
+            } else if model.status == ModelStatus::Degraded {
                 0.1
             } else {
                 0.0
             },
+            breaker_state: ejection,
+            fit_cost: breakdown.fit_cost,
+            fit_latency: breakdown.fit_latency,
+            fit_health: breakdown.fit_health,
+            fit_context: breakdown.fit_context,
         });
     }
 
-    scored.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
+    let kept_before_ranking: Vec<String> = scored.iter().map(|c| c.model.id.clone()).collect();
+    let ranked = rank_candidates(scored, &ctx.policy.selection);
+
+    let trace = trace.map(|mut map| {
+        let kept_after_ranking: std::collections::HashSet<&str> =
+            ranked.iter().map(|c| c.model.id.as_str()).collect();
+        for model_id in &kept_before_ranking {
+            if !kept_after_ranking.contains(model_id.as_str()) {
+                if let Some(entry) = map.get_mut(model_id) {
+                    entry.rejected = Some("pareto_dominated".to_string());
+                }
+            }
+        }
+        ScoringTrace {
+            selection_mode: ctx.policy.selection.mode,
+            candidates: map.into_values().collect(),
+        }
     });
-    Ok(scored)
+
+    Ok(ScoredCandidates {
+        candidates: ranked,
+        any_ejected,
+        trace,
+    })
+}
+
+fn rank_candidates<'a>(
+    mut scored: Vec<CandidateRef<'a>>,
+    selection: &PolicySelection,
+) -> Vec<CandidateRef<'a>> {
+    if selection.mode == SelectionMode::WeightedScore {
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        return scored;
+    }
+
+    let dominates = |a: &CandidateRef<'a>, b: &CandidateRef<'a>| -> bool {
+        a.fit_cost >= b.fit_cost
+            && a.fit_latency >= b.fit_latency
+            && a.fit_health >= b.fit_health
+            && a.fit_context >= b.fit_context
+            && (a.fit_cost > b.fit_cost
+                || a.fit_latency > b.fit_latency
+                || a.fit_health > b.fit_health
+                || a.fit_context > b.fit_context)
+    };
+
+    let mut frontier = Vec::with_capacity(scored.len());
+    for (idx, candidate) in scored.iter().enumerate() {
+        let dominator = scored
+            .iter()
+            .enumerate()
+            .find(|(other_idx, other)| *other_idx != idx && dominates(other, candidate));
+        match dominator {
+            Some((_, winner)) => {
+                tracing::debug!(
+                    eliminated = %candidate.model.id,
+                    dominated_by = %winner.model.id,
+                    "pareto selection eliminated dominated candidate"
+                );
+            }
+            None => frontier.push(idx),
+        }
+    }
+
+    let mut frontier: Vec<CandidateRef<'a>> = scored
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| frontier.contains(idx))
+        .map(|(_, candidate)| candidate)
+        .collect();
+
+    match selection.mode {
+        SelectionMode::ParetoWeighted => {
+            frontier.sort_by(|a, b| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        SelectionMode::ParetoLexicographic => {
+            frontier.sort_by(|a, b| lexicographic_cmp(a, b, selection));
+        }
+        SelectionMode::WeightedScore => unreachable!("handled above"),
+    }
+
+    frontier
+}
+
+fn fit_by_name(candidate: &CandidateRef<'_>, name: &str) -> f32 {
+    match name {
+        "cost" => candidate.fit_cost,
+        "latency" => candidate.fit_latency,
+        "health" => candidate.fit_health,
+        "context" => candidate.fit_context,
+        _ => 0.0,
+    }
+}
+
+fn lexicographic_cmp(
+    a: &CandidateRef<'_>,
+    b: &CandidateRef<'_>,
+    selection: &PolicySelection,
+) -> std::cmp::Ordering {
+    for name in &selection.priority {
+        let a_fit = fit_by_name(a, name);
+        let b_fit = fit_by_name(b, name);
+        if (a_fit - b_fit).abs() > selection.tolerance {
+            return b_fit.partial_cmp(&a_fit).unwrap_or(std::cmp::Ordering::Equal);
+        }
+    }
+    b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+}
+
+struct PromptCacheContext {
+    turns: u16,
+    overlay_size_bytes: u32,
+    base_ratio: f32,
+    ttl_decay: f32,
+}
+
+fn prompt_cache_fraction(model: &CompiledModel, in_tokens: u32, ctx: &PromptCacheContext) -> f32 {
+    if in_tokens == 0 {
+        return 0.0;
+    }
+    let turns = (ctx.turns.max(1)) as f32;
+    let avg_turn_tokens = in_tokens as f32 / turns;
+    let history_tokens = avg_turn_tokens * (turns - 1.0);
+    let overlay_tokens = ctx.overlay_size_bytes as f32 / BYTES_PER_TOKEN_ESTIMATE;
+    let stable_prefix_tokens =
+        (overlay_tokens + history_tokens).min(model.cache_window_tokens as f32);
+    let decay = ctx.ttl_decay.clamp(0.0, 1.0).powf(turns - 1.0);
+    (ctx.base_ratio.clamp(0.0, 1.0) * (stable_prefix_tokens / in_tokens as f32) * decay)
+        .clamp(0.0, 1.0)
 }
 
 fn estimate_cost_micro(
     model: &CompiledModel,
     in_tokens: u32,
     out_tokens: u32,
-    use_prompt_cache: bool,
+    prompt_cache: Option<&PromptCacheContext>,
 ) -> u64 {
-    let (cached_tokens, normal_tokens) = if use_prompt_cache {
-        let cached = ((in_tokens as f32) * 0.4).round() as u32;
-        (cached, in_tokens.saturating_sub(cached))
-    } else {
-        (0, in_tokens)
+    let (cached_tokens, normal_tokens) = match prompt_cache {
+        Some(ctx) => {
+            let fraction = prompt_cache_fraction(model, in_tokens, ctx);
+            let cached = ((in_tokens as f32) * fraction).round() as u32;
+            (cached, in_tokens.saturating_sub(cached))
+        }
+        None => (0, in_tokens),
     };
 
     let cached_cost =
@@ -1029,13 +2244,24 @@ fn estimate_latency(
     latency.round() as u32
 }
 
+#[derive(Clone, Copy)]
+struct ScoreBreakdown {
+    score: f32,
+    fit_cost: f32,
+    fit_latency: f32,
+    fit_health: f32,
+    fit_context: f32,
+    tier_bonus: f32,
+    degraded_penalty: f32,
+}
+
 fn compute_score(
     model: &CompiledModel,
     health: &HealthStats,
     factors: ScoreFactors,
     policy: &PolicyDocument,
     boost: bool,
-) -> f32 {
+) -> ScoreBreakdown {
     let defaults = &policy.defaults;
     let weights = &policy.weights;
     let cost_ratio = (factors.est_cost_micro as f32 / defaults.cost_norm_micro).min(1.5);
@@ -1057,15 +2283,62 @@ fn compute_score(
             .iter()
             .any(|tag| tag.eq_ignore_ascii_case("tier:T1"));
 
-    if has_bonus {
-        score += weights.tier_bonus;
+    let tier_bonus = if has_bonus { weights.tier_bonus } else { 0.0 };
+    score += tier_bonus;
+
+    let degraded_penalty = if model.status == ModelStatus::Degraded {
+        0.05
+    } else {
+        0.0
+    };
+    score -= degraded_penalty;
+
+    ScoreBreakdown {
+        score,
+        fit_cost,
+        fit_latency,
+        tier_bonus,
+        degraded_penalty,
+        fit_health,
+        fit_context,
     }
+}
 
-    if model.status == ModelStatus::Degraded {
-        score -= 0.05;
+const RETRYABLE_FAILURE_CLASSES: &[&str] = &["connection_reset", "timeout", "http_5xx"];
+const NON_RETRYABLE_FAILURE_CLASSES: &[&str] = &["auth", "http_4xx", "context_length"];
+
+fn build_retry_policy(retry: &PolicyRetry, timeout_ms: u32) -> RetryPolicy {
+    let max_attempts = retry.max_attempts.max(1);
+    let mut schedule = Vec::new();
+    let mut consumed_ms: u64 = 0;
+    let mut delay_ms = retry.base_ms as f64;
+
+    for attempt in 2..=max_attempts {
+        let delay = delay_ms.round().max(0.0) as u32;
+        let jitter = (delay as f32 * retry.jitter_ratio.max(0.0)).round() as u32;
+        let worst_case = consumed_ms + delay as u64 + jitter as u64;
+        if worst_case > timeout_ms as u64 {
+            break;
+        }
+        consumed_ms = worst_case;
+        schedule.push(RetryAttempt {
+            attempt,
+            delay_ms: delay,
+            max_jitter_ms: jitter,
+        });
+        delay_ms *= retry.multiplier.max(1.0) as f64;
     }
 
-    score
+    RetryPolicy {
+        max_attempts,
+        budget_ms: timeout_ms,
+        schedule,
+        retryable: RETRYABLE_FAILURE_CLASSES.iter().map(|s| s.to_string()).collect(),
+        non_retryable: NON_RETRYABLE_FAILURE_CLASSES
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    }
 }
 
 fn build_fallbacks<'a>(
@@ -1139,6 +2412,7 @@ fn materialize_plan(ctx: PlanAssembly<'_, '_>) -> Result<RoutePlan, RouterError>
             provider: Some(ctx.primary.model.provider.clone()),
         },
         fallbacks: ctx.fallbacks.to_vec(),
+        retry: build_retry_policy(&ctx.policy.retry, ctx.policy.defaults.timeout_ms),
         cache: CacheHints {
             ttl_ms: Some(ctx.cache_ttl_ms),
             etag: Some(format!(
@@ -1153,14 +2427,22 @@ fn materialize_plan(ctx: PlanAssembly<'_, '_>) -> Result<RoutePlan, RouterError>
             revision: Some(policy_revision.clone()),
             id: Some(ctx.policy.id.clone()),
             explain: Some(format!(
-                "score={:.3} cost={}Âµ latency={}ms",
-                ctx.primary.score, ctx.primary.est_cost_micro, ctx.primary.est_latency_ms
+                "score={:.3} cost={}Âµ latency={}ms{}",
+                ctx.primary.score,
+                ctx.primary.est_cost_micro,
+                ctx.primary.est_latency_ms,
+                match ctx.primary.breaker_state {
+                    EjectionState::Healthy => "",
+                    EjectionState::HalfOpen => " breaker=half_open_probe",
+                    EjectionState::Ejected => " breaker=ejected",
+                }
             )),
         },
         policy_rev: policy_revision.clone(),
         content_used: ctx.content_used,
         governance_echo: ctx.policy.governance.clone(),
         canonical: canonical_block,
+        scoring_trace: ctx.scoring_trace,
     };
 
     Ok(plan)
@@ -1226,6 +2508,13 @@ fn calc_cache_ratio(metrics: &RouterMetrics) -> f32 {
     }
 }
 
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 impl RouterEngine {
     fn attach_stickiness(
         &self,
@@ -1269,3 +2558,109 @@ impl RouterEngine {
         Ok(Some(claims))
     }
 }
+
+#[cfg(test)]
+mod pareto_tests {
+    use super::*;
+
+    fn test_model(id: &str) -> CompiledModel {
+        CompiledModel {
+            id: id.to_string(),
+            provider: "test".to_string(),
+            base_url: "https://example.invalid".to_string(),
+            mode: UpstreamMode::default(),
+            auth_env: None,
+            headers: HashMap::new(),
+            capabilities: CapabilityFlags::empty(),
+            regions: RegionMask::GLOBAL,
+            context_tokens: 8192,
+            cache_window_tokens: 8192,
+            prices: ModelPrice {
+                input_micro_per_million: 0,
+                output_micro_per_million: 0,
+                cached_micro_per_million: 0,
+            },
+            target_latency_ms: 500,
+            base_latency_ms: 500,
+            status: ModelStatus::Healthy,
+            policy_tags: Vec::new(),
+        }
+    }
+
+    fn candidate(
+        model: &CompiledModel,
+        score: f32,
+        fit_cost: f32,
+        fit_latency: f32,
+        fit_health: f32,
+        fit_context: f32,
+    ) -> CandidateRef<'_> {
+        CandidateRef {
+            model,
+            score,
+            est_cost_micro: 0,
+            est_latency_ms: 0,
+            penalty: 0.0,
+            breaker_state: EjectionState::Healthy,
+            fit_cost,
+            fit_latency,
+            fit_health,
+            fit_context,
+        }
+    }
+
+    fn selection(mode: SelectionMode, priority: &[&str], tolerance: f32) -> PolicySelection {
+        PolicySelection {
+            mode,
+            priority: priority.iter().map(|s| s.to_string()).collect(),
+            tolerance,
+        }
+    }
+
+    #[test]
+    fn rank_candidates_drops_dominated_candidates_from_frontier() {
+        let dominant = test_model("dominant");
+        let dominated = test_model("dominated");
+        let incomparable = test_model("incomparable");
+
+        // `dominated` loses on every axis to `dominant`, so it must not survive.
+        let scored = vec![
+            candidate(&dominant, 0.9, 0.8, 0.8, 0.8, 0.8),
+            candidate(&dominated, 0.5, 0.2, 0.2, 0.2, 0.2),
+            candidate(&incomparable, 0.7, 0.95, 0.1, 0.5, 0.5),
+        ];
+        let selection = selection(SelectionMode::ParetoWeighted, &[], 0.01);
+
+        let ranked = rank_candidates(scored, &selection);
+
+        let ids: Vec<&str> = ranked.iter().map(|c| c.model.id.as_str()).collect();
+        assert!(!ids.contains(&"dominated"));
+        assert!(ids.contains(&"dominant"));
+        assert!(ids.contains(&"incomparable"));
+        // ParetoWeighted breaks ties on the frontier by score, descending.
+        assert_eq!(ids[0], "dominant");
+    }
+
+    #[test]
+    fn lexicographic_cmp_prefers_higher_priority_axis_within_tolerance() {
+        let a = test_model("a");
+        let b = test_model("b");
+        let selection = selection(SelectionMode::ParetoLexicographic, &["latency", "cost"], 0.01);
+
+        // Tied on latency (within tolerance) but `a` wins on the next priority axis, cost.
+        let cand_a = candidate(&a, 0.5, 0.9, 0.5, 0.5, 0.5);
+        let cand_b = candidate(&b, 0.5, 0.1, 0.5, 0.5, 0.5);
+        assert_eq!(
+            lexicographic_cmp(&cand_a, &cand_b, &selection),
+            std::cmp::Ordering::Less
+        );
+
+        // Clear winner on the top-priority axis (latency) decides it before cost is checked.
+        let cand_a = candidate(&a, 0.5, 0.1, 0.9, 0.5, 0.5);
+        let cand_b = candidate(&b, 0.5, 0.9, 0.1, 0.5, 0.5);
+        assert_eq!(
+            lexicographic_cmp(&cand_a, &cand_b, &selection),
+            std::cmp::Ordering::Less
+        );
+    }
+}
@@ -17,6 +17,10 @@ pub enum ErrorCode {
     UpstreamUnavailable,
     PlanningFailed,
     InternalError,
+    Unauthenticated,
+    Forbidden,
+    IntegrityViolation,
+    OverlayDecryptionFailed,
 }
 
 impl ErrorCode {
@@ -32,6 +36,10 @@ impl ErrorCode {
             ErrorCode::UpstreamUnavailable => "UPSTREAM_UNAVAILABLE",
             ErrorCode::PlanningFailed => "PLANNING_FAILED",
             ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::Unauthenticated => "UNAUTHENTICATED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::IntegrityViolation => "INTEGRITY_VIOLATION",
+            ErrorCode::OverlayDecryptionFailed => "OVERLAY_DECRYPTION_FAILED",
         }
     }
 
@@ -47,6 +55,10 @@ impl ErrorCode {
             ErrorCode::UpstreamUnavailable => StatusCode::BAD_GATEWAY,
             ErrorCode::PlanningFailed => StatusCode::INTERNAL_SERVER_ERROR,
             ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::Unauthenticated => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::IntegrityViolation => StatusCode::CONFLICT,
+            ErrorCode::OverlayDecryptionFailed => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
@@ -56,12 +68,16 @@ impl ErrorCode {
             | ErrorCode::UnsupportedSchema
             | ErrorCode::InvalidApproval
             | ErrorCode::InvalidRequest
+            | ErrorCode::Unauthenticated
+            | ErrorCode::Forbidden
+            | ErrorCode::IntegrityViolation
             | ErrorCode::PolicyDeny => 0,
             ErrorCode::BudgetExceeded => 120_000,
             ErrorCode::CatalogUnavailable
             | ErrorCode::UpstreamUnavailable
             | ErrorCode::PlanningFailed
-            | ErrorCode::InternalError => 60_000,
+            | ErrorCode::InternalError
+            | ErrorCode::OverlayDecryptionFailed => 60_000,
         }
     }
 }
@@ -89,6 +105,10 @@ pub enum RouterError {
     InvalidRequest(String),
     #[error("policy denied: {0}")]
     PolicyDeny(String),
+    #[error("unauthenticated: {0}")]
+    Unauthenticated(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
     #[error("budget exceeded: {0}")]
     BudgetExceeded(String),
     #[error("catalog unavailable: {0}")]
@@ -97,6 +117,10 @@ pub enum RouterError {
     UpstreamUnavailable(String),
     #[error("routing failed: {0}")]
     Planning(String),
+    #[error("integrity check failed: {0}")]
+    IntegrityViolation(String),
+    #[error("overlay decryption failed: {0}")]
+    OverlayDecryption(String),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -111,10 +135,14 @@ impl RouterError {
             RouterError::InvalidApproval(_) => ErrorCode::InvalidApproval,
             RouterError::InvalidRequest(_) => ErrorCode::InvalidRequest,
             RouterError::PolicyDeny(_) => ErrorCode::PolicyDeny,
+            RouterError::Unauthenticated(_) => ErrorCode::Unauthenticated,
+            RouterError::Forbidden(_) => ErrorCode::Forbidden,
             RouterError::BudgetExceeded(_) => ErrorCode::BudgetExceeded,
             RouterError::CatalogUnavailable(_) => ErrorCode::CatalogUnavailable,
             RouterError::UpstreamUnavailable(_) => ErrorCode::UpstreamUnavailable,
             RouterError::Planning(_) => ErrorCode::PlanningFailed,
+            RouterError::IntegrityViolation(_) => ErrorCode::IntegrityViolation,
+            RouterError::OverlayDecryption(_) => ErrorCode::OverlayDecryptionFailed,
             RouterError::UnknownModel(_) => ErrorCode::InternalError,
             RouterError::Io(_) | RouterError::Any(_) => ErrorCode::InternalError,
         }
@@ -168,24 +196,20 @@ impl fmt::Display for ApiError {
     }
 }
 
-impl ResponseError for ApiError {
-    fn status_code(&self) -> StatusCode {
-        self.inner.code().status()
-    }
-
-    fn error_response(&self) -> HttpResponse {
-        #[derive(Debug, Serialize)]
-        struct ErrorBody {
-            schema_version: &'static str,
-            code: &'static str,
-            message: String,
-            request_id: String,
-            policy_rev: String,
-            retry_hint_ms: u64,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            supported: Option<Vec<String>>,
-        }
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub schema_version: &'static str,
+    pub code: &'static str,
+    pub message: String,
+    pub request_id: String,
+    pub policy_rev: String,
+    pub retry_hint_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supported: Option<Vec<String>>,
+}
 
+impl ApiError {
+    pub fn body(&self) -> ErrorBody {
         let request_id = self
             .context
             .request_id
@@ -197,7 +221,7 @@ impl ResponseError for ApiError {
             .clone()
             .unwrap_or_else(|| "unknown".into());
         let supported = self.inner.supported_versions().map(|slice| slice.to_vec());
-        let body = ErrorBody {
+        ErrorBody {
             schema_version: ERROR_SCHEMA_VERSION,
             code: self.inner.code().as_str(),
             message: self.inner.to_string(),
@@ -205,8 +229,17 @@ impl ResponseError for ApiError {
             policy_rev,
             retry_hint_ms: self.inner.retry_hint_ms(),
             supported,
-        };
-        HttpResponse::build(self.status_code()).json(body)
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.inner.code().status()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self.body())
     }
 }
 
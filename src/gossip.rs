@@ -0,0 +1,138 @@
+use crate::cache::{CacheKey, PlanCache};
+use crate::config::GossipConfig;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MAX_PACKET_BYTES: usize = 2048;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GossipMessage {
+    origin: String,
+    seq: u64,
+    kind: GossipKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum GossipKind {
+    InvalidateAll { policy_rev: String },
+    InvalidateKeys { policy_rev: String, keys: Vec<u64> },
+}
+
+pub struct GossipHandle {
+    socket: tokio::net::UdpSocket,
+    peers: Vec<String>,
+    fanout: usize,
+    origin: String,
+    seq: AtomicU64,
+}
+
+impl GossipHandle {
+    pub async fn bind(cfg: &GossipConfig) -> std::io::Result<Option<Self>> {
+        if !cfg.enabled || cfg.peers.is_empty() {
+            return Ok(None);
+        }
+        let socket = tokio::net::UdpSocket::bind(&cfg.bind_addr).await?;
+        Ok(Some(Self {
+            socket,
+            peers: cfg.peers.clone(),
+            fanout: cfg.fanout.max(1),
+            origin: cfg.bind_addr.clone(),
+            seq: AtomicU64::new(0),
+        }))
+    }
+
+    pub async fn broadcast_invalidate_all(&self, policy_rev: &str) {
+        self.broadcast(GossipKind::InvalidateAll {
+            policy_rev: policy_rev.to_string(),
+        })
+        .await;
+    }
+
+    pub async fn broadcast_invalidate_keys(&self, policy_rev: &str, keys: &[CacheKey]) {
+        self.broadcast(GossipKind::InvalidateKeys {
+            policy_rev: policy_rev.to_string(),
+            keys: keys.iter().map(|key| key.0).collect(),
+        })
+        .await;
+    }
+
+    async fn broadcast(&self, kind: GossipKind) {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed) + 1;
+        let message = GossipMessage {
+            origin: self.origin.clone(),
+            seq,
+            kind,
+        };
+        self.send_to_peers(&message).await;
+    }
+
+    async fn send_to_peers(&self, message: &GossipMessage) {
+        let Ok(payload) = serde_json::to_vec(message) else {
+            return;
+        };
+        let mut rng = rand::thread_rng();
+        let chosen = self
+            .peers
+            .choose_multiple(&mut rng, self.fanout.min(self.peers.len()));
+        for peer in chosen {
+            if let Err(err) = self.socket.send_to(&payload, peer).await {
+                tracing::warn!("gossip send to {peer} failed: {err}");
+            }
+        }
+    }
+
+    pub async fn run(&self, cache: PlanCache) {
+        let mut seen: HashMap<String, u64> = HashMap::new();
+        let mut buf = vec![0u8; MAX_PACKET_BYTES];
+        loop {
+            let (len, _from) = match self.socket.recv_from(&mut buf).await {
+                Ok(pair) => pair,
+                Err(err) => {
+                    tracing::warn!("gossip recv failed: {err}");
+                    continue;
+                }
+            };
+            let message: GossipMessage = match serde_json::from_slice(&buf[..len]) {
+                Ok(message) => message,
+                Err(err) => {
+                    tracing::warn!("gossip message parse failed: {err}");
+                    continue;
+                }
+            };
+            if message.origin == self.origin {
+                continue;
+            }
+            let last_seen = seen.get(&message.origin).copied().unwrap_or(0);
+            if message.seq <= last_seen {
+                continue;
+            }
+            seen.insert(message.origin.clone(), message.seq);
+
+            match &message.kind {
+                GossipKind::InvalidateAll { policy_rev } => {
+                    tracing::info!(
+                        policy_rev,
+                        origin = %message.origin,
+                        "gossip: invalidating full plan cache"
+                    );
+                    cache.invalidate_all_raw().await;
+                }
+                GossipKind::InvalidateKeys { policy_rev, keys } => {
+                    tracing::info!(
+                        policy_rev,
+                        origin = %message.origin,
+                        count = keys.len(),
+                        "gossip: invalidating plan cache keys"
+                    );
+                    cache
+                        .invalidate_keys_raw(keys.iter().map(|key| CacheKey(*key)))
+                        .await;
+                }
+            }
+
+            self.send_to_peers(&message).await;
+        }
+    }
+}
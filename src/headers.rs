@@ -0,0 +1,40 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderMap, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+const SCOPED_PATHS: [&str; 2] = ["/route/plan", "/catalog/models"];
+
+pub async fn response_headers(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let scoped = SCOPED_PATHS.iter().any(|path| req.path().starts_with(path));
+    let mut res = next.call(req).await?;
+    if scoped {
+        let headers = res.headers_mut();
+        headers.insert(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+        headers
+            .entry(header::CACHE_CONTROL)
+            .or_insert_with(|| HeaderValue::from_static("no-cache, must-revalidate"));
+        append_vary(headers, "Accept-Encoding");
+    }
+    Ok(res)
+}
+
+fn append_vary(headers: &mut HeaderMap, value: &str) {
+    let combined = match headers.get(header::VARY).and_then(|v| v.to_str().ok()) {
+        Some(existing) if existing.split(',').any(|v| v.trim().eq_ignore_ascii_case(value)) => {
+            return;
+        }
+        Some(existing) => format!("{existing}, {value}"),
+        None => value.to_string(),
+    };
+    if let Ok(header_value) = HeaderValue::from_str(&combined) {
+        headers.insert(header::VARY, header_value);
+    }
+}
@@ -1,8 +1,22 @@
 use crate::types::RouteFeedback;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use std::sync::Arc;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EjectionState {
+    Healthy,
+    Ejected,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub success: bool,
+    pub latency_ms: u32,
+    pub checked_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HealthStats {
     pub p50_ms: f32,
@@ -10,6 +24,16 @@ pub struct HealthStats {
     pub err_rate: f32,
     pub tokens_per_sec: f32,
     pub last_update: DateTime<Utc>,
+    pub last_probe: Option<ProbeResult>,
+    pub sample_count: u32,
+    ejected_until: Option<DateTime<Utc>>,
+    half_open: bool,
+    consecutive_ejections: u32,
+    last_cooldown_ms: u64,
+    p50_estimator: P2Estimator,
+    p95_estimator: P2Estimator,
+    pub breaker_state: EjectionState,
+    pub breaker_cooldown_remaining_ms: Option<u64>,
 }
 
 impl Default for HealthStats {
@@ -20,6 +44,16 @@ impl Default for HealthStats {
             err_rate: 0.01,
             tokens_per_sec: 300.0,
             last_update: Utc::now(),
+            last_probe: None,
+            sample_count: 0,
+            ejected_until: None,
+            half_open: false,
+            consecutive_ejections: 0,
+            last_cooldown_ms: 0,
+            p50_estimator: P2Estimator::new(0.5),
+            p95_estimator: P2Estimator::new(0.95),
+            breaker_state: EjectionState::Healthy,
+            breaker_cooldown_remaining_ms: None,
         }
     }
 }
@@ -51,12 +85,18 @@ impl HealthStore {
 
     pub fn update(&self, feedback: &RouteFeedback) {
         let mut entry = self.inner.entry(feedback.model_id.clone()).or_default();
-        let alpha = 0.2_f32;
-        let latency = feedback.duration_ms as f32;
-        entry.p50_ms = blend(entry.p50_ms, latency, alpha);
-        entry.p95_ms = blend(entry.p95_ms, latency * 1.3, alpha / 2.0);
+        let latency = feedback.duration_ms as f64;
+        entry.p50_estimator.observe(latency);
+        entry.p95_estimator.observe(latency);
+        if let Some(p50) = entry.p50_estimator.value() {
+            entry.p50_ms = p50 as f32;
+        }
+        if let Some(p95) = entry.p95_estimator.value() {
+            entry.p95_ms = p95 as f32;
+        }
         let err = if feedback.success { 0.0 } else { 1.0 };
         entry.err_rate = blend(entry.err_rate, err, 0.1);
+        entry.sample_count = entry.sample_count.saturating_add(1);
         if let Some(usage) = &feedback.usage {
             let total_tokens = (usage.prompt_tokens + usage.completion_tokens) as f32;
             if feedback.duration_ms > 0 {
@@ -65,9 +105,221 @@ impl HealthStore {
             }
         }
         entry.last_update = Utc::now();
+
+        if entry.half_open {
+            entry.half_open = false;
+            if feedback.success {
+                entry.ejected_until = None;
+                entry.consecutive_ejections = 0;
+                entry.last_cooldown_ms = 0;
+                entry.breaker_state = EjectionState::Healthy;
+                entry.breaker_cooldown_remaining_ms = None;
+            } else {
+                let cooldown_ms = entry.last_cooldown_ms.max(1).saturating_mul(2);
+                entry.last_cooldown_ms = cooldown_ms;
+                entry.ejected_until = Some(Utc::now() + Duration::milliseconds(cooldown_ms as i64));
+                entry.breaker_state = EjectionState::Ejected;
+                entry.breaker_cooldown_remaining_ms = Some(cooldown_ms);
+            }
+        }
+    }
+
+    pub fn check_ejection(
+        &self,
+        model_id: &str,
+        threshold: f32,
+        min_samples: u32,
+        base_cooldown_ms: u64,
+    ) -> EjectionState {
+        let mut entry = self.inner.entry(model_id.to_string()).or_default();
+        let now = Utc::now();
+
+        if let Some(until) = entry.ejected_until {
+            if entry.half_open {
+                // probe already in flight; only that caller gets HalfOpen
+                entry.breaker_state = EjectionState::Ejected;
+                entry.breaker_cooldown_remaining_ms = Some(0);
+                return EjectionState::Ejected;
+            }
+            if now < until {
+                let remaining = (until - now).num_milliseconds().max(0) as u64;
+                entry.breaker_state = EjectionState::Ejected;
+                entry.breaker_cooldown_remaining_ms = Some(remaining);
+                return EjectionState::Ejected;
+            }
+            entry.half_open = true;
+            entry.breaker_state = EjectionState::HalfOpen;
+            entry.breaker_cooldown_remaining_ms = Some(0);
+            return EjectionState::HalfOpen;
+        }
+
+        if entry.sample_count >= min_samples && entry.err_rate > threshold {
+            entry.consecutive_ejections = entry.consecutive_ejections.saturating_add(1);
+            let growth = 1u64 << entry.consecutive_ejections.saturating_sub(1).min(6);
+            let cooldown_ms = base_cooldown_ms.saturating_mul(growth);
+            entry.last_cooldown_ms = cooldown_ms;
+            entry.ejected_until = Some(now + Duration::milliseconds(cooldown_ms as i64));
+            entry.half_open = false;
+            entry.breaker_state = EjectionState::Ejected;
+            entry.breaker_cooldown_remaining_ms = Some(cooldown_ms);
+            return EjectionState::Ejected;
+        }
+
+        entry.breaker_state = EjectionState::Healthy;
+        entry.breaker_cooldown_remaining_ms = None;
+        EjectionState::Healthy
+    }
+
+    pub fn record_probe(&self, model_id: &str, success: bool, latency_ms: u32) {
+        let mut entry = self.inner.entry(model_id.to_string()).or_default();
+        if success {
+            entry.p50_estimator.observe(latency_ms as f64);
+            if let Some(p50) = entry.p50_estimator.value() {
+                entry.p50_ms = p50 as f32;
+            }
+        }
+        let err = if success { 0.0 } else { 1.0 };
+        entry.err_rate = blend(entry.err_rate, err, 0.1);
+        entry.last_probe = Some(ProbeResult {
+            success,
+            latency_ms,
+            checked_at: Utc::now(),
+        });
+        entry.last_update = Utc::now();
     }
 }
 
 fn blend(prev: f32, new: f32, alpha: f32) -> f32 {
     prev + (new - prev) * alpha.clamp(0.0, 1.0)
 }
+
+#[derive(Debug, Clone)]
+struct P2Estimator {
+    quantile: f64,
+    seed: Vec<f64>,
+    initialized: bool,
+    markers: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Estimator {
+    fn new(quantile: f64) -> Self {
+        Self {
+            quantile,
+            seed: Vec::with_capacity(5),
+            initialized: false,
+            markers: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [
+                1.0,
+                1.0 + 2.0 * quantile,
+                1.0 + 4.0 * quantile,
+                3.0 + 2.0 * quantile,
+                5.0,
+            ],
+            increments: [0.0, quantile / 2.0, quantile, (1.0 + quantile) / 2.0, 1.0],
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.initialized {
+            Some(self.markers[2])
+        } else {
+            None
+        }
+    }
+
+    fn observe(&mut self, x: f64) {
+        if !self.initialized {
+            self.seed.push(x);
+            if self.seed.len() < 5 {
+                return;
+            }
+            self.seed.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            self.markers.copy_from_slice(&self.seed);
+            self.initialized = true;
+            return;
+        }
+
+        let k = if x < self.markers[0] {
+            self.markers[0] = x;
+            0
+        } else if x >= self.markers[4] {
+            self.markers[4] = x;
+            3
+        } else {
+            (0..4)
+                .find(|&i| self.markers[i] <= x && x < self.markers[i + 1])
+                .unwrap_or(3)
+        };
+
+        for position in self.positions.iter_mut().skip(k + 1) {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments.iter()) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let right_gap = self.positions[i + 1] - self.positions[i];
+            let left_gap = self.positions[i - 1] - self.positions[i];
+            if (d >= 1.0 && right_gap > 1.0) || (d <= -1.0 && left_gap < -1.0) {
+                let s = d.signum();
+                let parabolic = self.markers[i]
+                    + s / (self.positions[i + 1] - self.positions[i - 1])
+                        * ((self.positions[i] - self.positions[i - 1] + s)
+                            * (self.markers[i + 1] - self.markers[i])
+                            / (self.positions[i + 1] - self.positions[i])
+                            + (self.positions[i + 1] - self.positions[i] - s)
+                                * (self.markers[i] - self.markers[i - 1])
+                                / (self.positions[i] - self.positions[i - 1]));
+                self.markers[i] = if self.markers[i - 1] < parabolic && parabolic < self.markers[i + 1]
+                {
+                    parabolic
+                } else if s > 0.0 {
+                    self.markers[i]
+                        + (self.markers[i + 1] - self.markers[i])
+                            / (self.positions[i + 1] - self.positions[i])
+                } else {
+                    self.markers[i]
+                        - (self.markers[i - 1] - self.markers[i])
+                            / (self.positions[i - 1] - self.positions[i])
+                };
+                self.positions[i] += s;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn p2_estimator_converges_to_known_quantiles() {
+        let n = 10_000u64;
+        let mut p50 = P2Estimator::new(0.5);
+        let mut p95 = P2Estimator::new(0.95);
+        // feed in a fixed shuffled order, not sorted, to match a real stream
+        for i in 0..n {
+            let x = ((i * 7919) % n) as f64;
+            p50.observe(x);
+            p95.observe(x);
+        }
+        let expected_p50 = (n - 1) as f64 * 0.5;
+        let expected_p95 = (n - 1) as f64 * 0.95;
+        let got_p50 = p50.value().expect("p50 initialized after n samples");
+        let got_p95 = p95.value().expect("p95 initialized after n samples");
+        assert!(
+            (got_p50 - expected_p50).abs() < expected_p50 * 0.05,
+            "p50 estimate {got_p50} too far from expected {expected_p50}"
+        );
+        assert!(
+            (got_p95 - expected_p95).abs() < expected_p95 * 0.05,
+            "p95 estimate {got_p95} too far from expected {expected_p95}"
+        );
+    }
+}
@@ -1,12 +1,12 @@
 use actix_cors::Cors;
-use actix_web::{middleware::Logger, web, App, HttpServer};
+use actix_web::{middleware, middleware::Logger, web, App, HttpServer};
 use anyhow::Context;
 use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use router::config::RouterConfig;
 use router::engine::RouterEngine;
-use router::{api, errors};
+use router::{api, auth, compression, errors, headers};
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,6 +24,27 @@ async fn main() -> anyhow::Result<()> {
 
     let engine = RouterEngine::bootstrap(&cfg).await?;
     let shared_engine = web::Data::new(engine);
+    shared_engine
+        .clone()
+        .into_inner()
+        .spawn_health_prober(cfg.health_probe.clone());
+    shared_engine.clone().into_inner().spawn_hot_reload(
+        cfg.policy_path.clone(),
+        cfg.catalog_path.clone(),
+        cfg.hot_reload.clone(),
+    );
+    shared_engine
+        .clone()
+        .into_inner()
+        .spawn_cache_persistence(cfg.cache_persist.clone());
+    shared_engine
+        .clone()
+        .into_inner()
+        .spawn_gossip(cfg.gossip.clone());
+    let credentials = web::Data::new(cfg.credentials.clone());
+    let admin_tokens = web::Data::new(cfg.admin_tokens.clone());
+    let cors_data = web::Data::new(cfg.cors.clone());
+    let compression_cfg = cfg.compression;
 
     let bind_addr: SocketAddr = cfg.server.bind_addr.parse().with_context(|| {
         format!(
@@ -32,19 +53,50 @@ async fn main() -> anyhow::Result<()> {
         )
     })?;
 
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allowed_methods(vec!["GET", "POST", "PUT"])
-            .allowed_headers(vec![
-                actix_web::http::header::CONTENT_TYPE,
-                actix_web::http::header::ACCEPT,
-                actix_web::http::header::AUTHORIZATION,
-            ])
-            .max_age(3600);
+    let tls_config = match (&cfg.server.tls_cert_path, &cfg.server.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            Some(router::config::load_tls_server_config(cert_path, key_path)?)
+        }
+        _ => None,
+    };
 
+    let server = HttpServer::new(move || {
+        let mut cors = Cors::default();
+        cors = if cors_data.allowed_origins.is_empty() {
+            cors.allow_any_origin()
+        } else {
+            let allowed = cors_data.allowed_origins.clone();
+            cors.allowed_origin_fn(move |origin, _req_head| {
+                origin
+                    .to_str()
+                    .map(|value| allowed.iter().any(|o| o == value))
+                    .unwrap_or(false)
+            })
+        };
+        cors = cors
+            .allowed_methods(cors_data.allowed_methods.iter().map(String::as_str))
+            .allowed_headers(cors_data.allowed_headers.iter().map(String::as_str))
+            .expose_headers(cors_data.exposed_headers.iter().map(String::as_str))
+            .max_age(Some(cors_data.max_age_secs));
+        if cors_data.allow_credentials && !cors_data.allowed_origins.is_empty() {
+            cors = cors.supports_credentials();
+        }
+
+        let min_size_bytes = compression_cfg.min_size_bytes;
         App::new()
             .wrap(Logger::default())
+            .wrap(middleware::Condition::new(
+                compression_cfg.enabled,
+                middleware::from_fn(move |req, next| {
+                    compression::size_gate(req, next, min_size_bytes)
+                }),
+            ))
+            .wrap(middleware::Condition::new(
+                compression_cfg.enabled,
+                middleware::Compress::default(),
+            ))
+            .wrap(middleware::from_fn(headers::response_headers))
+            .wrap(middleware::from_fn(auth::authenticate))
             .wrap(cors)
             .app_data(
                 web::JsonConfig::default()
@@ -52,12 +104,19 @@ async fn main() -> anyhow::Result<()> {
                     .error_handler(|err, _| errors::json_error(err)),
             )
             .app_data(shared_engine.clone())
+            .app_data(credentials.clone())
+            .app_data(admin_tokens.clone())
+            .app_data(cors_data.clone())
             .configure(api::configure)
     })
-    .bind(bind_addr)?
-    .workers(cfg.server.workers)
-    .run()
-    .await?;
+    .workers(cfg.server.workers);
+
+    let server = match tls_config {
+        Some(tls) => server.bind_rustls_0_23(bind_addr, tls)?,
+        None => server.bind(bind_addr)?,
+    };
+
+    server.run().await?;
 
     Ok(())
 }
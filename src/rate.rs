@@ -1,6 +1,10 @@
 use dashmap::DashMap;
+use std::marker::PhantomData;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::time::interval;
 
 #[derive(Debug, Clone)]
 pub struct RateLimiter {
@@ -15,6 +19,37 @@ struct Bucket {
     last_refill: Instant,
 }
 
+#[derive(Debug, Clone, Copy, Error)]
+#[error("requested cost {cost} exceeds bucket capacity {capacity}")]
+pub struct CostExceedsCapacity {
+    pub cost: f64,
+    pub capacity: f64,
+}
+
+pub const DEFAULT_IPV6_PREFIX_LEN: u8 = 64;
+
+fn ip_key(addr: IpAddr, prefix_len: u8) -> String {
+    match addr {
+        IpAddr::V4(v4) => v4.to_string(),
+        IpAddr::V6(v6) => {
+            let prefix_len = prefix_len.min(128);
+            let bits = u128::from(v6);
+            let masked = if prefix_len == 0 {
+                0
+            } else {
+                bits & (!0u128 << (128 - prefix_len))
+            };
+            format!("{}/{}", std::net::Ipv6Addr::from(masked), prefix_len)
+        }
+    }
+}
+
+fn bucket_would_be_full(bucket: &Bucket, capacity: f64, refill_per_sec: f64, now: Instant) -> bool {
+    let elapsed = now.duration_since(bucket.last_refill);
+    let projected = bucket.tokens + elapsed.as_secs_f64() * refill_per_sec;
+    projected >= capacity
+}
+
 impl RateLimiter {
     pub fn new(capacity: f64, refill_per_sec: f64) -> Self {
         Self {
@@ -46,4 +81,325 @@ impl RateLimiter {
             false
         }
     }
+
+    pub fn check_ip(&self, addr: IpAddr, ipv6_prefix_len: u8) -> bool {
+        self.check(&ip_key(addr, ipv6_prefix_len))
+    }
+
+    pub async fn acquire(&self, key: &str) -> Result<(), CostExceedsCapacity> {
+        self.acquire_n(key, 1.0).await
+    }
+
+    pub async fn acquire_n(&self, key: &str, cost: f64) -> Result<(), CostExceedsCapacity> {
+        if cost > self.capacity {
+            return Err(CostExceedsCapacity {
+                cost,
+                capacity: self.capacity,
+            });
+        }
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut entry = self
+                    .buckets
+                    .entry(key.to_string())
+                    .or_insert_with(|| Bucket {
+                        tokens: self.capacity,
+                        last_refill: now,
+                    });
+                let elapsed = now.duration_since(entry.last_refill);
+                if elapsed > Duration::ZERO {
+                    let refill = elapsed.as_secs_f64() * self.refill_per_sec;
+                    entry.tokens = (entry.tokens + refill).min(self.capacity);
+                    entry.last_refill = now;
+                }
+                if entry.tokens >= cost {
+                    entry.tokens -= cost;
+                    None
+                } else {
+                    Some((cost - entry.tokens) / self.refill_per_sec)
+                }
+            };
+            match wait {
+                None => return Ok(()),
+                Some(seconds) => tokio::time::sleep(Duration::from_secs_f64(seconds.max(0.0))).await,
+            }
+        }
+    }
+
+    pub fn cleanup(&self) {
+        let now = Instant::now();
+        let capacity = self.capacity;
+        let refill_per_sec = self.refill_per_sec;
+        self.buckets
+            .retain(|_, bucket| !bucket_would_be_full(bucket, capacity, refill_per_sec, now));
+    }
+
+    pub fn spawn_cleanup(&self, interval_ms: u64) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_millis(interval_ms.max(1)));
+            loop {
+                ticker.tick().await;
+                limiter.cleanup();
+            }
+        })
+    }
+}
+
+pub trait RateLimitAction: Copy {
+    fn index(self) -> usize;
+}
+
+#[derive(Debug, Clone)]
+pub struct TieredRateLimiter<A: RateLimitAction, const N: usize> {
+    tiers: [RateLimiter; N],
+    _action: PhantomData<A>,
+}
+
+impl<A: RateLimitAction, const N: usize> TieredRateLimiter<A, N> {
+    pub fn new(configs: [(f64, f64); N]) -> Self {
+        Self {
+            tiers: configs.map(|(capacity, refill_per_sec)| RateLimiter::new(capacity, refill_per_sec)),
+            _action: PhantomData,
+        }
+    }
+
+    pub fn check(&self, action: A, key: &str) -> bool {
+        self.tiers[action.index()].check(key)
+    }
+
+    pub fn cleanup(&self) {
+        for tier in &self.tiers {
+            tier.cleanup();
+        }
+    }
+
+    pub fn spawn_cleanup(&self, interval_ms: u64) -> Vec<tokio::task::JoinHandle<()>> {
+        self.tiers
+            .iter()
+            .map(|tier| tier.spawn_cleanup(interval_ms))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WindowConfig {
+    pub capacity: f64,
+    pub refill_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WindowRejection {
+    pub window_index: usize,
+    pub retry_after: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct MultiWindowLimiter {
+    windows: Arc<Vec<WindowConfig>>,
+    buckets: Arc<DashMap<String, Vec<Bucket>>>,
+}
+
+impl MultiWindowLimiter {
+    pub fn new(windows: Vec<WindowConfig>) -> Self {
+        Self {
+            windows: Arc::new(windows),
+            buckets: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn check(&self, key: &str) -> Result<(), WindowRejection> {
+        let now = Instant::now();
+        let windows = &self.windows;
+        let mut entry = self.buckets.entry(key.to_string()).or_insert_with(|| {
+            windows
+                .iter()
+                .map(|w| Bucket {
+                    tokens: w.capacity,
+                    last_refill: now,
+                })
+                .collect()
+        });
+        for (bucket, window) in entry.iter_mut().zip(windows.iter()) {
+            let elapsed = now.duration_since(bucket.last_refill);
+            if elapsed > Duration::ZERO {
+                bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * window.refill_per_sec)
+                    .min(window.capacity);
+                bucket.last_refill = now;
+            }
+        }
+        if let Some((window_index, (bucket, window))) = entry
+            .iter()
+            .zip(windows.iter())
+            .enumerate()
+            .find(|(_, (bucket, _))| bucket.tokens < 1.0)
+        {
+            let deficit = 1.0 - bucket.tokens;
+            let retry_after = Duration::from_secs_f64((deficit / window.refill_per_sec).max(0.0));
+            return Err(WindowRejection {
+                window_index,
+                retry_after,
+            });
+        }
+        for bucket in entry.iter_mut() {
+            bucket.tokens -= 1.0;
+        }
+        Ok(())
+    }
+
+    pub fn cleanup(&self) {
+        let now = Instant::now();
+        let windows = &self.windows;
+        self.buckets.retain(|_, buckets| {
+            buckets
+                .iter()
+                .zip(windows.iter())
+                .any(|(bucket, window)| {
+                    !bucket_would_be_full(bucket, window.capacity, window.refill_per_sec, now)
+                })
+        });
+    }
+
+    pub fn spawn_cleanup(&self, interval_ms: u64) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_millis(interval_ms.max(1)));
+            loop {
+                ticker.tick().await;
+                limiter.cleanup();
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CompactBucket {
+    tokens: f32,
+    last_refill_offset_secs: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompactRateLimiter {
+    buckets: Arc<DashMap<String, CompactBucket>>,
+    capacity: f32,
+    refill_per_sec: f32,
+    epoch: Instant,
+}
+
+impl CompactRateLimiter {
+    pub fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Self {
+            buckets: Arc::new(DashMap::new()),
+            capacity: capacity as f32,
+            refill_per_sec: refill_per_sec as f32,
+            epoch: Instant::now(),
+        }
+    }
+
+    fn offset_secs(&self, now: Instant) -> u32 {
+        now.saturating_duration_since(self.epoch)
+            .as_secs()
+            .min(u32::MAX as u64) as u32
+    }
+
+    pub fn check(&self, key: &str) -> bool {
+        let now_offset = self.offset_secs(Instant::now());
+        let mut entry = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| CompactBucket {
+                tokens: self.capacity,
+                last_refill_offset_secs: now_offset,
+            });
+        let elapsed_secs = now_offset.saturating_sub(entry.last_refill_offset_secs);
+        if elapsed_secs > 0 {
+            let refill = elapsed_secs as f32 * self.refill_per_sec;
+            entry.tokens = (entry.tokens + refill).min(self.capacity);
+            entry.last_refill_offset_secs = now_offset;
+        }
+        if entry.tokens >= 1.0 {
+            entry.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn cleanup(&self) {
+        let now_offset = self.offset_secs(Instant::now());
+        let capacity = self.capacity;
+        let refill_per_sec = self.refill_per_sec;
+        self.buckets.retain(|_, bucket| {
+            let elapsed_secs = now_offset.saturating_sub(bucket.last_refill_offset_secs);
+            let projected = bucket.tokens + elapsed_secs as f32 * refill_per_sec;
+            projected < capacity
+        });
+    }
+
+    pub fn spawn_cleanup(&self, interval_ms: u64) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_millis(interval_ms.max(1)));
+            loop {
+                ticker.tick().await;
+                limiter.cleanup();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_key_shares_bucket_within_same_v6_prefix() {
+        let a: IpAddr = "2001:db8:1234:5678::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1234:5678:ffff:ffff:ffff:ffff".parse().unwrap();
+        assert_eq!(ip_key(a, 64), ip_key(b, 64));
+    }
+
+    #[test]
+    fn ip_key_separates_different_v6_prefixes() {
+        let a: IpAddr = "2001:db8:1234:5678::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1234:5679::1".parse().unwrap();
+        assert_ne!(ip_key(a, 64), ip_key(b, 64));
+    }
+
+    #[test]
+    fn check_ip_shares_bucket_within_same_v6_prefix() {
+        let limiter = RateLimiter::new(1.0, 0.0);
+        let a: IpAddr = "2001:db8:1234:5678::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1234:5678::2".parse().unwrap();
+        assert!(limiter.check_ip(a, 64));
+        // Same /64 as `a`, so the single token is already spent.
+        assert!(!limiter.check_ip(b, 64));
+    }
+
+    #[test]
+    fn check_ip_separates_different_v6_prefixes() {
+        let limiter = RateLimiter::new(1.0, 0.0);
+        let a: IpAddr = "2001:db8:1234:5678::1".parse().unwrap();
+        let c: IpAddr = "2001:db8:9999:9999::1".parse().unwrap();
+        assert!(limiter.check_ip(a, 64));
+        // Different /64, so `c` still has its own full bucket.
+        assert!(limiter.check_ip(c, 64));
+    }
+
+    #[test]
+    fn compact_bucket_is_smaller_than_bucket() {
+        assert!(std::mem::size_of::<CompactBucket>() < std::mem::size_of::<Bucket>());
+    }
+
+    #[test]
+    fn compact_rate_limiter_matches_rate_limiter_admission() {
+        let limiter = RateLimiter::new(3.0, 0.0);
+        let compact = CompactRateLimiter::new(3.0, 0.0);
+        for _ in 0..5 {
+            let expected = limiter.check("k");
+            let actual = compact.check("k");
+            assert_eq!(expected, actual);
+        }
+    }
 }
@@ -21,19 +21,41 @@ pub struct StickinessClaims {
 }
 
 #[derive(Clone)]
-pub struct StickinessManager {
+struct SigningKey {
+    id: u8,
     secret: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct StickinessManager {
+    keys: Vec<SigningKey>,
     engine: base64::engine::general_purpose::GeneralPurpose,
 }
 
 impl StickinessManager {
     pub fn new(secret: Vec<u8>) -> Self {
+        Self::with_keys(vec![(0, secret)])
+    }
+
+    pub fn with_keys(keys: Vec<(u8, Vec<u8>)>) -> Self {
+        assert!(!keys.is_empty(), "StickinessManager requires at least one signing key");
         Self {
-            secret,
+            keys: keys
+                .into_iter()
+                .map(|(id, secret)| SigningKey { id, secret })
+                .collect(),
             engine: base64::engine::general_purpose::URL_SAFE_NO_PAD,
         }
     }
 
+    fn primary(&self) -> &SigningKey {
+        &self.keys[0]
+    }
+
+    fn key_by_id(&self, id: u8) -> Option<&SigningKey> {
+        self.keys.iter().find(|key| key.id == id)
+    }
+
     pub fn issue(
         &self,
         tenant: Option<&str>,
@@ -77,18 +99,24 @@ impl StickinessManager {
             .engine
             .decode(token)
             .map_err(|err| RouterError::InvalidApproval(format!("bad token encoding: {err}")))?;
-        if raw.len() < 32 {
+        if raw.len() < 34 {
             return Err(RouterError::InvalidApproval("token too short".into()));
         }
-        let (payload, sig) = raw.split_at(raw.len() - 32);
+        let (key_id, rest) = raw.split_first().expect("checked length above");
+        let (signed, sig) = rest.split_at(rest.len() - 32);
+
+        let key = self.key_by_id(*key_id).ok_or_else(|| {
+            RouterError::InvalidApproval(format!("unknown signing key id {key_id}"))
+        })?;
 
-        let mut mac = HmacSha256::new_from_slice(&self.secret)
+        let mut mac = HmacSha256::new_from_slice(&key.secret)
             .map_err(|_| RouterError::InvalidApproval("invalid secret".into()))?;
-        mac.update(payload);
+        mac.update(&[*key_id]);
+        mac.update(signed);
         mac.verify_slice(sig)
             .map_err(|_| RouterError::InvalidApproval("signature mismatch".into()))?;
 
-        let claims: StickinessClaims = serde_json::from_slice(payload)
+        let claims: StickinessClaims = serde_json::from_slice(signed)
             .map_err(|err| RouterError::InvalidApproval(format!("invalid claims: {err}")))?;
 
         if claims.expires_at < Utc::now() {
@@ -101,11 +129,16 @@ impl StickinessManager {
     fn sign_claims(&self, claims: StickinessClaims) -> Result<String, RouterError> {
         let payload = serde_json::to_vec(&claims)
             .map_err(|err| RouterError::InvalidApproval(format!("serialize claims: {err}")))?;
-        let mut mac = HmacSha256::new_from_slice(&self.secret)
+        let key = self.primary();
+        let mut mac = HmacSha256::new_from_slice(&key.secret)
             .map_err(|_| RouterError::InvalidApproval("invalid secret".into()))?;
+        mac.update(&[key.id]);
         mac.update(&payload);
         let sig = mac.finalize().into_bytes();
-        let mut out = payload;
+
+        let mut out = Vec::with_capacity(1 + payload.len() + sig.len());
+        out.push(key.id);
+        out.extend_from_slice(&payload);
         out.extend_from_slice(&sig);
         Ok(self.engine.encode(out))
     }
@@ -0,0 +1,231 @@
+use crate::engine::RouterEngine;
+use crate::types::{Fallback, FeedbackUsage, RouteFeedback, RoutePlan, UpstreamMode};
+use actix_web::http::header;
+use actix_web::web::{Bytes, Data};
+use actix_web::HttpResponse;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde_json::Value;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+const DEFAULT_IDLE_TIMEOUT_MS: u32 = 30_000;
+
+pub async fn proxy_stream(
+    engine: Data<RouterEngine>,
+    client: reqwest::Client,
+    request_params: Option<Value>,
+    plan: RoutePlan,
+) -> HttpResponse {
+    let idle_timeout = Duration::from_millis(
+        plan.limits.timeout_ms.unwrap_or(DEFAULT_IDLE_TIMEOUT_MS) as u64,
+    );
+    let fallback = plan.fallbacks.first().cloned();
+    let route_id = plan.route_id.clone();
+    let model_id = plan.upstream.model_id.clone();
+
+    let url = format!(
+        "{}{}",
+        plan.upstream.base_url.trim_end_matches('/'),
+        upstream_path(&plan.upstream.mode)
+    );
+    let mut body = request_params.unwrap_or_else(|| Value::Object(Default::default()));
+    if let Value::Object(map) = &mut body {
+        map.insert("model".into(), Value::String(model_id.clone()));
+        map.insert("stream".into(), Value::Bool(true));
+    }
+
+    let mut builder = client.post(url.as_str()).json(&body);
+    for (name, value) in &plan.upstream.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if let Some(env_name) = plan.upstream.auth_env.as_ref() {
+        if let Ok(key) = std::env::var(env_name) {
+            builder = builder.bearer_auth(key);
+        }
+    }
+
+    let started = Instant::now();
+    let upstream = match builder.send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            let reason = format!("upstream responded with status {}", resp.status());
+            return terminal_error_stream(&engine, route_id, model_id, started, fallback, reason);
+        }
+        Err(err) => {
+            let reason = format!("upstream connection failed: {err}");
+            return terminal_error_stream(&engine, route_id, model_id, started, fallback, reason);
+        }
+    };
+
+    let state = StreamState {
+        inner: Box::pin(upstream.bytes_stream()),
+        engine,
+        route_id,
+        model_id,
+        started,
+        idle_timeout,
+        fallback,
+        usage: FeedbackUsage::default(),
+        done: false,
+    };
+
+    let body = stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        match tokio::time::timeout(state.idle_timeout, state.inner.next()).await {
+            Ok(Some(Ok(chunk))) => {
+                accumulate_usage(&mut state.usage, &chunk);
+                Some((Ok::<Bytes, actix_web::Error>(chunk), state))
+            }
+            Ok(Some(Err(err))) => {
+                let event = finish_with_error(&mut state, format!("upstream stream error: {err}"));
+                Some((Ok(event), state))
+            }
+            Ok(None) => {
+                finish_ok(&mut state);
+                None
+            }
+            Err(_) => {
+                let event =
+                    finish_with_error(&mut state, "idle timeout waiting for next chunk".into());
+                Some((Ok(event), state))
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .append_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
+}
+
+fn upstream_path(mode: &UpstreamMode) -> &'static str {
+    match mode {
+        UpstreamMode::Chat => "/v1/chat/completions",
+        UpstreamMode::Responses => "/v1/responses",
+    }
+}
+
+struct StreamState {
+    inner: Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>,
+    engine: Data<RouterEngine>,
+    route_id: String,
+    model_id: String,
+    started: Instant,
+    idle_timeout: Duration,
+    fallback: Option<Fallback>,
+    usage: FeedbackUsage,
+    done: bool,
+}
+
+fn finish_ok(state: &mut StreamState) {
+    state.done = true;
+    let feedback = build_feedback(state, true, None);
+    state.engine.health().update(&feedback);
+}
+
+fn finish_with_error(state: &mut StreamState, reason: String) -> Bytes {
+    state.done = true;
+    let feedback = build_feedback(state, false, Some(reason.clone()));
+    state.engine.health().update(&feedback);
+    Bytes::from(render_error_event(state.fallback.as_ref(), &reason))
+}
+
+fn terminal_error_stream(
+    engine: &Data<RouterEngine>,
+    route_id: String,
+    model_id: String,
+    started: Instant,
+    fallback: Option<Fallback>,
+    reason: String,
+) -> HttpResponse {
+    let feedback = RouteFeedback {
+        route_id,
+        model_id,
+        success: false,
+        duration_ms: started.elapsed().as_millis() as u32,
+        usage: None,
+        status_code: 0,
+        actual_cost_micro: None,
+        currency: None,
+        upstream_error_code: Some(reason.clone()),
+        rl_applied: None,
+        cache_hit: None,
+    };
+    engine.health().update(&feedback);
+    let event = render_error_event(fallback.as_ref(), &reason);
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .append_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream::once(async move {
+            Ok::<Bytes, actix_web::Error>(Bytes::from(event))
+        }))
+}
+
+fn build_feedback(
+    state: &StreamState,
+    success: bool,
+    upstream_error_code: Option<String>,
+) -> RouteFeedback {
+    RouteFeedback {
+        route_id: state.route_id.clone(),
+        model_id: state.model_id.clone(),
+        success,
+        duration_ms: state.started.elapsed().as_millis() as u32,
+        usage: Some(state.usage.clone()),
+        status_code: if success { 200 } else { 0 },
+        actual_cost_micro: None,
+        currency: None,
+        upstream_error_code,
+        rl_applied: None,
+        cache_hit: None,
+    }
+}
+
+fn render_error_event(fallback: Option<&Fallback>, reason: &str) -> String {
+    let payload = match fallback {
+        Some(fallback) => serde_json::json!({
+            "error": {
+                "message": reason,
+                "fallback_model_id": fallback.model_id,
+                "fallback_reason": fallback.reason,
+            }
+        }),
+        None => serde_json::json!({ "error": { "message": reason } }),
+    };
+    format!("event: error\ndata: {payload}\n\n")
+}
+
+fn accumulate_usage(usage: &mut FeedbackUsage, chunk: &Bytes) {
+    let Ok(text) = std::str::from_utf8(chunk) else {
+        return;
+    };
+    for line in text.lines() {
+        let Some(payload) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let payload = payload.trim();
+        if payload.is_empty() || payload == "[DONE]" {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<Value>(payload) else {
+            continue;
+        };
+        let Some(reported) = value.get("usage") else {
+            continue;
+        };
+        if let Some(v) = reported.get("prompt_tokens").and_then(Value::as_u64) {
+            usage.prompt_tokens = v as u32;
+        }
+        if let Some(v) = reported.get("completion_tokens").and_then(Value::as_u64) {
+            usage.completion_tokens = v as u32;
+        }
+        if let Some(v) = reported.get("cached_tokens").and_then(Value::as_u64) {
+            usage.cached_tokens = v as u32;
+        }
+        if let Some(v) = reported.get("reasoning_tokens").and_then(Value::as_u64) {
+            usage.reasoning_tokens = v as u32;
+        }
+    }
+}
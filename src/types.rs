@@ -163,6 +163,46 @@ pub struct Fallback {
     pub penalty: Option<f32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CandidateTrace {
+    pub model_id: String,
+    pub score: f32,
+    pub fit_cost: f32,
+    pub fit_latency: f32,
+    pub fit_health: f32,
+    pub fit_context: f32,
+    pub weight_cost: f32,
+    pub weight_latency: f32,
+    pub weight_health: f32,
+    pub weight_context: f32,
+    pub tier_bonus: f32,
+    pub degraded_penalty: f32,
+    pub canonical_hint_delta: f32,
+    pub rejected: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ScoringTrace {
+    pub selection_mode: SelectionMode,
+    pub candidates: Vec<CandidateTrace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub delay_ms: u32,
+    pub max_jitter_ms: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub budget_ms: u32,
+    pub schedule: Vec<RetryAttempt>,
+    pub retryable: Vec<String>,
+    pub non_retryable: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CacheHints {
     pub ttl_ms: Option<u32>,
@@ -222,6 +262,7 @@ pub struct RoutePlan {
     pub prompt_overlays: PromptOverlays,
     pub hints: Hints,
     pub fallbacks: Vec<Fallback>,
+    pub retry: RetryPolicy,
     pub cache: CacheHints,
     pub stickiness: Stickiness,
     pub policy: PolicyInfo,
@@ -231,6 +272,9 @@ pub struct RoutePlan {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub canonical: Option<CanonicalContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub scoring_trace: Option<ScoringTrace>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -287,6 +331,8 @@ pub struct CatalogModelCapabilities {
     pub prompt_cache: bool,
     #[serde(default)]
     pub structured_output: bool,
+    #[serde(default)]
+    pub prompt_cache_window_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -334,6 +380,16 @@ pub struct CatalogDocument {
     pub revision: String,
     #[serde(default = "Vec::new")]
     pub models: Vec<CatalogModel>,
+    #[serde(default)]
+    pub integrity: Option<IntegrityEnvelope>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IntegrityEnvelope {
+    #[serde(default)]
+    pub expected_sha256: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -374,6 +430,50 @@ pub struct PolicyWeights {
     pub tier_bonus: f32,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[derive(Default)]
+pub enum SelectionMode {
+    #[default]
+    WeightedScore,
+    ParetoLexicographic,
+    ParetoWeighted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySelection {
+    pub mode: SelectionMode,
+    #[serde(default = "PolicySelection::default_priority")]
+    pub priority: Vec<String>,
+    #[serde(default = "PolicySelection::default_tolerance")]
+    pub tolerance: f32,
+}
+
+impl PolicySelection {
+    fn default_priority() -> Vec<String> {
+        vec![
+            "latency".to_string(),
+            "cost".to_string(),
+            "health".to_string(),
+            "context".to_string(),
+        ]
+    }
+
+    fn default_tolerance() -> f32 {
+        0.05
+    }
+}
+
+impl Default for PolicySelection {
+    fn default() -> Self {
+        Self {
+            mode: SelectionMode::WeightedScore,
+            priority: Self::default_priority(),
+            tolerance: Self::default_tolerance(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyStickiness {
     pub window_ms: u64,
@@ -388,6 +488,20 @@ pub struct PolicyDefaults {
     pub max_output_tokens: u32,
     pub max_overlay_bytes: u32,
     pub stickiness: PolicyStickiness,
+    #[serde(default = "PolicyDefaults::default_cache_base_ratio")]
+    pub cache_base_ratio: f32,
+    #[serde(default = "PolicyDefaults::default_cache_ttl_decay")]
+    pub cache_ttl_decay: f32,
+}
+
+impl PolicyDefaults {
+    fn default_cache_base_ratio() -> f32 {
+        0.4
+    }
+
+    fn default_cache_ttl_decay() -> f32 {
+        0.9
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -406,6 +520,42 @@ pub struct PolicyTier {
     pub description: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyEjection {
+    pub error_rate_threshold: f32,
+    pub min_samples: u32,
+    pub base_cooldown_ms: u64,
+}
+
+impl Default for PolicyEjection {
+    fn default() -> Self {
+        Self {
+            error_rate_threshold: 0.5,
+            min_samples: 20,
+            base_cooldown_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRetry {
+    pub max_attempts: u32,
+    pub base_ms: u32,
+    pub multiplier: f32,
+    pub jitter_ratio: f32,
+}
+
+impl Default for PolicyRetry {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_ms: 200,
+            multiplier: 2.0,
+            jitter_ratio: 0.2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyDocument {
     pub id: String,
@@ -418,6 +568,12 @@ pub struct PolicyDocument {
     #[serde(default)]
     pub escalations: PolicyEscalations,
     #[serde(default)]
+    pub ejection: PolicyEjection,
+    #[serde(default)]
+    pub retry: PolicyRetry,
+    #[serde(default)]
+    pub selection: PolicySelection,
+    #[serde(default)]
     pub tiers: HashMap<String, PolicyTier>,
     #[serde(default)]
     pub aliases: HashMap<String, PolicyAliasRule>,
@@ -425,4 +581,6 @@ pub struct PolicyDocument {
     pub overlay_map: HashMap<String, HashMap<String, String>>,
     #[serde(default)]
     pub overlay_defaults: HashMap<String, String>,
+    #[serde(default)]
+    pub integrity: Option<IntegrityEnvelope>,
 }
@@ -3,7 +3,10 @@ use actix_web::{
     test, web, App,
 };
 use router::api;
-use router::config::{RouterConfig, ServerConfig};
+use router::config::{
+    CachePersistConfig, GossipConfig, HealthProbeConfig, HotReloadConfig, RouterConfig,
+    ServerConfig,
+};
 use router::engine::RouterEngine;
 use router::types::{CatalogDocument, PolicyDocument};
 use serde_json::{json, Value};
@@ -25,15 +28,51 @@ fn test_router_config() -> RouterConfig {
         server: ServerConfig {
             bind_addr: "127.0.0.1:0".into(),
             workers: 1,
+            tls_cert_path: None,
+            tls_key_path: None,
         },
         overlay_dir: PathBuf::from("configs/overlays"),
         cache_ttl_ms: 60,
         cache_stale_ms: 60,
-        sticky_secret: b"test-secret-key".to_vec(),
+        cache_idle_ttl_ms: 0,
+        sticky_keys: vec![(0, b"test-secret-key".to_vec())],
         policy,
+        policy_path: PathBuf::from("configs/policy.json"),
         catalog,
+        catalog_path: PathBuf::from("configs/catalog.json"),
         rate_limit_burst: 500.0,
         rate_limit_refill_per_sec: 500.0,
+        batch_concurrency_limit: 16,
+        embedding: None,
+        health_probe: HealthProbeConfig {
+            enabled: false,
+            interval_ms: 30_000,
+            timeout_ms: 2_000,
+            path: "/healthz".into(),
+        },
+        credentials: Default::default(),
+        admin_tokens: Default::default(),
+        hot_reload: HotReloadConfig {
+            enabled: false,
+            allow_equal_revision: false,
+            debounce_ms: 300,
+        },
+        cache_persist: CachePersistConfig {
+            enabled: false,
+            path: PathBuf::from("./data/plan_cache.bin"),
+            max_items: 10_000,
+            interval_ms: 60_000,
+        },
+        gossip: GossipConfig {
+            enabled: false,
+            bind_addr: "0.0.0.0:7946".into(),
+            peers: Vec::new(),
+            fanout: 3,
+        },
+        reload_signing_public_key: None,
+        overlay_encryption_key: None,
+        cors: Default::default(),
+        compression: Default::default(),
     }
 }
 